@@ -0,0 +1,182 @@
+//! Compressed sparse row storage for the graph's edges
+
+use std::fmt;
+
+/// An edge's node or neighbour that is not below the node count
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NodeOutOfRange {
+    pub node: usize,
+    pub node_count: usize,
+}
+
+/// Why an edge could not be appended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdjacencyError {
+    /// Edges of a later node have already been appended
+    OutOfOrder {
+        node: u32,
+    },
+    NodeOutOfRange(NodeOutOfRange),
+}
+
+impl fmt::Display for NodeOutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "node {} is out of range for {} nodes",
+            self.node, self.node_count
+        )
+    }
+}
+
+impl fmt::Display for AdjacencyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AdjacencyError::OutOfOrder { node } => {
+                write!(f, "edges of node {} must be appended in node order", node)
+            }
+            AdjacencyError::NodeOutOfRange(error) => error.fmt(f),
+        }
+    }
+}
+
+/// Each node's neighbours and edge weights, stored contiguously in node order
+///
+/// Edges can be appended while they arrive in node order, as they do from
+/// the streaming loader, without rebuilding the rows already stored.
+#[derive(Debug, Clone)]
+pub struct Adjacency {
+    node_count: usize,
+    // Start of each row up to the last row appended to; later rows are empty
+    offsets: Vec<u32>,
+    neighbours: Vec<u32>,
+    weights: Vec<f32>,
+}
+
+impl Adjacency {
+    pub fn new(node_count: usize) -> Adjacency {
+        Adjacency {
+            node_count,
+            offsets: Vec::new(),
+            neighbours: Vec::new(),
+            weights: Vec::new(),
+        }
+    }
+
+    /// Builds rows from (node, neighbour, weight) edges in any order, keeping
+    /// the order of each node's edges
+    pub fn from_edges(
+        node_count: usize,
+        edges: &mut [(u32, u32, f32)],
+    ) -> Result<Adjacency, AdjacencyError> {
+        edges.sort_by_key(|&(node, _, _)| node);
+        let mut adjacency = Adjacency::new(node_count);
+        for &(node, neighbour, weight) in edges.iter() {
+            adjacency.append(node, neighbour, weight)?;
+        }
+        Ok(adjacency)
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.neighbours.len()
+    }
+
+    /// Whether edges of `node` can be appended, which needs every later
+    /// node's row to still be empty
+    pub fn can_append(&self, node: u32) -> bool {
+        node as usize + 1 >= self.offsets.len()
+    }
+
+    /// Adds an edge after the last, leaving the rows unchanged on error
+    pub fn append(&mut self, node: u32, neighbour: u32, weight: f32) -> Result<(), AdjacencyError> {
+        if let Some(&out_of_range) = [node, neighbour]
+            .iter()
+            .find(|&&index| index as usize >= self.node_count)
+        {
+            return Err(AdjacencyError::NodeOutOfRange(NodeOutOfRange {
+                node: out_of_range as usize,
+                node_count: self.node_count,
+            }));
+        }
+        if !self.can_append(node) {
+            return Err(AdjacencyError::OutOfOrder { node });
+        }
+        let edge_index = u32::try_from(self.neighbours.len()).expect("Edge count should fit u32");
+        while self.offsets.len() <= node as usize {
+            self.offsets.push(edge_index);
+        }
+        self.neighbours.push(neighbour);
+        self.weights.push(weight);
+        Ok(())
+    }
+
+    fn row(&self, node: usize) -> std::ops::Range<usize> {
+        match self.offsets.get(node) {
+            Some(&start) => {
+                let end = match self.offsets.get(node + 1) {
+                    Some(&end) => end as usize,
+                    None => self.neighbours.len(),
+                };
+                start as usize..end
+            }
+            None => self.neighbours.len()..self.neighbours.len(),
+        }
+    }
+
+    /// Nodes out of range have no neighbours
+    pub fn neighbours(&self, node: usize) -> &[u32] {
+        &self.neighbours[self.row(node)]
+    }
+
+    /// Weights of the edges to each of `neighbours(node)`, in the same order
+    pub fn weights(&self, node: usize) -> &[f32] {
+        &self.weights[self.row(node)]
+    }
+
+    /// Every (node, neighbour, weight) edge, in row order
+    pub fn edges(&self) -> impl Iterator<Item = (u32, u32, f32)> + '_ {
+        (0..self.node_count).flat_map(move |node| {
+            let row = self.row(node);
+            self.neighbours[row.clone()]
+                .iter()
+                .zip(&self.weights[row])
+                .map(move |(&neighbour, &weight)| (node as u32, neighbour, weight))
+        })
+    }
+
+    /// Swaps the direction of every edge, so targets become sources
+    ///
+    /// Each row of the result lists its neighbours in ascending order.
+    pub fn transpose(&self) -> Adjacency {
+        let mut offsets = vec![0; self.node_count + 1];
+        for &neighbour in &self.neighbours {
+            offsets[neighbour as usize + 1] += 1;
+        }
+        for node in 0..self.node_count {
+            offsets[node + 1] += offsets[node];
+        }
+        let mut next = offsets.clone();
+        let mut neighbours = vec![0; self.neighbours.len()];
+        let mut weights = vec![0.0; self.weights.len()];
+        for (node, neighbour, weight) in self.edges() {
+            let edge_index = next[neighbour as usize] as usize;
+            neighbours[edge_index] = node;
+            weights[edge_index] = weight;
+            next[neighbour as usize] += 1;
+        }
+        offsets.pop();
+        Adjacency {
+            node_count: self.node_count,
+            offsets,
+            neighbours,
+            weights,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,94 @@
+use super::*;
+
+#[test]
+fn test_append_in_node_order() {
+    let mut adjacency = Adjacency::new(4);
+
+    adjacency.append(0, 2, 1.0).unwrap();
+    adjacency.append(2, 0, 0.5).unwrap();
+    adjacency.append(2, 3, 2.0).unwrap();
+
+    assert_eq!(adjacency.neighbours(0), &[2]);
+    assert_eq!(adjacency.neighbours(1), &[] as &[u32]);
+    assert_eq!(adjacency.neighbours(2), &[0, 3]);
+    assert_eq!(adjacency.weights(2), &[0.5, 2.0]);
+    assert_eq!(adjacency.neighbours(3), &[] as &[u32]);
+    assert_eq!(adjacency.edge_count(), 3);
+}
+
+#[test]
+fn test_can_append() {
+    let mut adjacency = Adjacency::new(3);
+
+    adjacency.append(1, 0, 1.0).unwrap();
+
+    assert!(!adjacency.can_append(0));
+    assert!(adjacency.can_append(1));
+    assert!(adjacency.can_append(2));
+}
+
+#[test]
+fn test_append_out_of_order() {
+    let mut adjacency = Adjacency::new(3);
+    adjacency.append(1, 0, 1.0).unwrap();
+
+    let result = adjacency.append(0, 1, 1.0);
+
+    assert_eq!(result, Err(AdjacencyError::OutOfOrder { node: 0 }));
+    assert_eq!(adjacency.edge_count(), 1);
+}
+
+#[test]
+fn test_append_out_of_range() {
+    let mut adjacency = Adjacency::new(3);
+
+    let source_result = adjacency.append(3, 0, 1.0);
+    let target_result = adjacency.append(0, 5, 1.0);
+
+    assert_eq!(
+        source_result,
+        Err(AdjacencyError::NodeOutOfRange(NodeOutOfRange {
+            node: 3,
+            node_count: 3
+        }))
+    );
+    assert_eq!(
+        target_result,
+        Err(AdjacencyError::NodeOutOfRange(NodeOutOfRange {
+            node: 5,
+            node_count: 3
+        }))
+    );
+    assert_eq!(adjacency.edge_count(), 0);
+    assert_eq!(adjacency.neighbours(3), &[] as &[u32]);
+}
+
+#[test]
+fn test_from_edges_keeps_order_within_rows() {
+    let mut edges = vec![(2, 1, 1.0), (0, 2, 2.0), (2, 0, 3.0), (0, 1, 4.0)];
+
+    let adjacency = Adjacency::from_edges(3, &mut edges).unwrap();
+
+    assert_eq!(adjacency.neighbours(0), &[2, 1]);
+    assert_eq!(adjacency.weights(0), &[2.0, 4.0]);
+    assert_eq!(adjacency.neighbours(2), &[1, 0]);
+    assert_eq!(
+        adjacency.edges().collect::<Vec<_>>(),
+        vec![(0, 2, 2.0), (0, 1, 4.0), (2, 1, 1.0), (2, 0, 3.0)]
+    );
+}
+
+#[test]
+fn test_transpose() {
+    let mut edges = vec![(0, 1, 1.0), (0, 2, 2.0), (2, 1, 3.0), (1, 1, 4.0)];
+    let adjacency = Adjacency::from_edges(4, &mut edges).unwrap();
+
+    let transposed = adjacency.transpose();
+
+    assert_eq!(transposed.neighbours(0), &[] as &[u32]);
+    assert_eq!(transposed.neighbours(1), &[0, 1, 2]);
+    assert_eq!(transposed.weights(1), &[1.0, 4.0, 3.0]);
+    assert_eq!(transposed.neighbours(2), &[0]);
+    assert_eq!(transposed.neighbours(3), &[] as &[u32]);
+    assert_eq!(transposed.edge_count(), 4);
+}
@@ -0,0 +1,710 @@
+use crate::random::Rng;
+use crate::GraphLayout;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use wasm_bindgen::prelude::*;
+
+/// Which edges to follow when measuring distances from a node
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Follow edges from sources to targets
+    Out,
+    /// Follow edges backwards from targets to sources
+    In,
+}
+
+fn neighbours(layout: &GraphLayout, node: usize, direction: Direction) -> &[u32] {
+    match direction {
+        Direction::Out => layout.targets(node),
+        Direction::In => layout.sources(node),
+    }
+}
+
+/// Weights of the edges to each of `neighbours(layout, node, direction)`
+fn neighbour_weights(layout: &GraphLayout, node: usize, direction: Direction) -> &[f32] {
+    match direction {
+        Direction::Out => layout.target_weights(node),
+        Direction::In => layout.source_weights(node),
+    }
+}
+
+/// Length of an edge for shortest paths
+///
+/// Weights measure how strong a tie is, so heavier edges are shorter. Unit
+/// weights give lengths of one, so distances count hops in unweighted
+/// graphs. Edges of zero weight have no length and are never followed.
+fn edge_length(weight: f32) -> Option<f64> {
+    if weight > 0.0 {
+        Some(1.0 / weight as f64)
+    } else {
+        None
+    }
+}
+
+/// Whether two path lengths are equal up to the rounding of f32 weights, so
+/// that paths summed in a different order still count as equally short
+fn same_length(first: f64, second: f64) -> bool {
+    (first - second).abs() <= 1e-6 * first.abs().max(second.abs())
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct CentralityScores {
+    values: Vec<f32>,
+    converged: bool,
+    iterations: usize,
+}
+
+#[wasm_bindgen]
+impl CentralityScores {
+    pub fn scores(&self) -> js_sys::Float32Array {
+        js_sys::Float32Array::from(self.values.as_slice())
+    }
+
+    /// Node indices ordered from highest to lowest score
+    pub fn ranking(&self) -> js_sys::Uint32Array {
+        js_sys::Uint32Array::from(rank_order(&self.values).as_slice())
+    }
+
+    pub fn converged(&self) -> bool {
+        self.converged
+    }
+
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+}
+
+impl CentralityScores {
+    pub fn new(values: Vec<f32>, converged: bool, iterations: usize) -> CentralityScores {
+        CentralityScores {
+            values,
+            converged,
+            iterations,
+        }
+    }
+
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+}
+
+#[wasm_bindgen]
+pub struct HitsScores {
+    hubs: CentralityScores,
+    authorities: CentralityScores,
+}
+
+#[wasm_bindgen]
+impl HitsScores {
+    pub fn hubs(&self) -> CentralityScores {
+        self.hubs.clone()
+    }
+
+    pub fn authorities(&self) -> CentralityScores {
+        self.authorities.clone()
+    }
+}
+
+/// Orders node indices by descending score, breaking ties by index
+pub fn rank_order(scores: &[f32]) -> Vec<u32> {
+    let mut order: Vec<u32> = (0..scores.len() as u32).collect();
+    order.sort_by(|&a, &b| {
+        scores[b as usize]
+            .total_cmp(&scores[a as usize])
+            .then(a.cmp(&b))
+    });
+    order
+}
+
+/// Computes PageRank by power iteration over the outgoing edges of each node
+///
+/// Each node passes its rank to its targets in proportion to edge weight. The
+/// rank held by dangling nodes (those without targets) is spread evenly over
+/// the whole graph. Iteration stops once the L1 distance between
+/// successive rank vectors falls below `tolerance`.
+pub fn pagerank(
+    layout: &GraphLayout,
+    damping: f32,
+    tolerance: f32,
+    max_iter: usize,
+) -> CentralityScores {
+    let node_count = layout.node_count();
+    let teleport = vec![1.0 / node_count as f64; node_count];
+    let initial = teleport.clone();
+    teleporting_pagerank(layout, &teleport, initial, damping, tolerance, max_iter)
+}
+
+/// Computes PageRank with teleportation restricted to the seed nodes
+///
+/// Random walks restart only at the seeds, so the scores measure how
+/// relevant each node is to them rather than to the graph as a whole.
+pub fn personalized_pagerank(
+    layout: &GraphLayout,
+    seeds: &[usize],
+    damping: f32,
+    tolerance: f32,
+    max_iter: usize,
+) -> CentralityScores {
+    let mut teleport = vec![0.0; layout.node_count()];
+    for &seed in seeds {
+        teleport[seed] += 1.0 / seeds.len() as f64;
+    }
+    let initial = teleport.clone();
+    teleporting_pagerank(layout, &teleport, initial, damping, tolerance, max_iter)
+}
+
+/// Returns up to `count` of the highest scoring nodes, skipping `excluded`
+pub fn top_nodes(scores: &[f32], count: usize, excluded: &[usize]) -> Vec<u32> {
+    rank_order(scores)
+        .into_iter()
+        .filter(|&node| !excluded.contains(&(node as usize)))
+        .take(count)
+        .collect()
+}
+
+/// PageRank estimate refined as edges stream in
+///
+/// Each call to `refine` runs a few power iterations starting from the
+/// previous estimate, which is usually close to the answer for the larger
+/// graph, so rankings settle progressively while the graph loads.
+pub struct IncrementalPageRank {
+    scores: CentralityScores,
+    damping: f32,
+    tolerance: f32,
+    iterations_per_chunk: usize,
+}
+
+impl IncrementalPageRank {
+    pub fn new(
+        layout: &GraphLayout,
+        damping: f32,
+        tolerance: f32,
+        iterations_per_chunk: usize,
+    ) -> IncrementalPageRank {
+        let node_count = layout.node_count();
+        let uniform = vec![1.0 / node_count as f32; node_count];
+        IncrementalPageRank {
+            scores: CentralityScores::new(uniform, false, 0),
+            damping,
+            tolerance,
+            iterations_per_chunk,
+        }
+    }
+
+    pub fn refine(&mut self, layout: &GraphLayout) {
+        let node_count = layout.node_count();
+        let teleport = vec![1.0 / node_count as f64; node_count];
+        let initial = self.scores.values.iter().map(|&rank| rank as f64).collect();
+        let refined = teleporting_pagerank(
+            layout,
+            &teleport,
+            initial,
+            self.damping,
+            self.tolerance,
+            self.iterations_per_chunk,
+        );
+        self.scores = CentralityScores::new(
+            refined.values,
+            refined.converged,
+            self.scores.iterations + refined.iterations,
+        );
+    }
+
+    pub fn scores(&self) -> &CentralityScores {
+        &self.scores
+    }
+}
+
+/// PageRank where both the random jumps and the rank of dangling nodes are
+/// distributed according to `teleport`, which should sum to one
+fn teleporting_pagerank(
+    layout: &GraphLayout,
+    teleport: &[f64],
+    initial: Vec<f64>,
+    damping: f32,
+    tolerance: f32,
+    max_iter: usize,
+) -> CentralityScores {
+    let node_count = layout.node_count();
+    if node_count == 0 {
+        return CentralityScores::new(Vec::new(), true, 0);
+    }
+    let damping = damping as f64;
+    let out_weights: Vec<f64> = (0..node_count)
+        .map(|node| {
+            layout
+                .target_weights(node)
+                .iter()
+                .map(|&weight| weight as f64)
+                .sum()
+        })
+        .collect();
+
+    power_iterate(initial, tolerance, max_iter, |ranks, next_ranks| {
+        // Nodes without outgoing weight are treated as dangling
+        let dangling_rank: f64 = (0..node_count)
+            .filter(|&node| out_weights[node] <= 0.0)
+            .map(|node| ranks[node])
+            .sum();
+        let jump_rank = 1.0 - damping + damping * dangling_rank;
+        for (next_rank, share) in next_ranks.iter_mut().zip(teleport.iter()) {
+            *next_rank = jump_rank * share;
+        }
+        for (source, rank) in ranks.iter().enumerate() {
+            if out_weights[source] <= 0.0 {
+                continue;
+            }
+            let share = damping * rank / out_weights[source];
+            let targets = layout.targets(source);
+            for (&target, &weight) in targets.iter().zip(layout.target_weights(source)) {
+                next_ranks[target as usize] += share * weight as f64;
+            }
+        }
+    })
+}
+
+/// Computes eigenvector centrality from the edge-weighted scores of each
+/// node's sources
+///
+/// Iterates `x + Aᵀx` rather than `Aᵀx`, which has the same leading
+/// eigenvector but also converges on periodic graphs such as cycles. Scores
+/// are scaled to unit Euclidean length.
+pub fn eigenvector(layout: &GraphLayout, tolerance: f32, max_iter: usize) -> CentralityScores {
+    let node_count = layout.node_count();
+    let initial = vec![1.0 / node_count as f64; node_count];
+    power_iterate(initial, tolerance, max_iter, |scores, next_scores| {
+        for (node, next_score) in next_scores.iter_mut().enumerate() {
+            *next_score = scores[node] + weighted_source_sum(layout, node, scores);
+        }
+        scale_to_unit_length(next_scores);
+    })
+}
+
+/// Computes Katz centrality, `x = αAᵀx + β`
+///
+/// Only converges when `alpha` is below the reciprocal of the largest
+/// eigenvalue of the adjacency matrix. When `normalized` is set the scores are
+/// scaled to unit Euclidean length.
+pub fn katz(
+    layout: &GraphLayout,
+    alpha: f32,
+    beta: f32,
+    tolerance: f32,
+    max_iter: usize,
+    normalized: bool,
+) -> CentralityScores {
+    let node_count = layout.node_count();
+    let alpha = alpha as f64;
+    let beta = beta as f64;
+    let initial = vec![0.0; node_count];
+    let mut result = power_iterate(initial, tolerance, max_iter, |scores, next_scores| {
+        for (node, next_score) in next_scores.iter_mut().enumerate() {
+            *next_score = alpha * weighted_source_sum(layout, node, scores) + beta;
+        }
+    });
+    if normalized {
+        let length = result
+            .values
+            .iter()
+            .map(|&value| value * value)
+            .sum::<f32>()
+            .sqrt();
+        if length > 0.0 {
+            result.values.iter_mut().for_each(|value| *value /= length);
+        }
+    }
+    result
+}
+
+/// Computes HITS hub and authority scores
+///
+/// Authorities are pointed to by good hubs and hubs point to good
+/// authorities. Both score vectors are scaled to sum to one.
+pub fn hits(layout: &GraphLayout, tolerance: f32, max_iter: usize) -> HitsScores {
+    let node_count = layout.node_count();
+    let mut authorities = vec![0.0; node_count];
+    let initial = vec![1.0 / node_count as f64; node_count];
+    let hubs = power_iterate(initial, tolerance, max_iter, |hubs, next_hubs| {
+        authority_step(layout, hubs, &mut authorities);
+        for (node, next_hub) in next_hubs.iter_mut().enumerate() {
+            *next_hub = weighted_target_sum(layout, node, &authorities);
+        }
+        scale_to_unit_sum(next_hubs);
+    });
+    // Authorities must match the final hub scores, not the previous ones
+    let hub_values: Vec<f64> = hubs.values.iter().map(|&hub| hub as f64).collect();
+    authority_step(layout, &hub_values, &mut authorities);
+    let authorities = CentralityScores::new(
+        authorities
+            .iter()
+            .map(|&authority| authority as f32)
+            .collect(),
+        hubs.converged,
+        hubs.iterations,
+    );
+    HitsScores { hubs, authorities }
+}
+
+fn authority_step(layout: &GraphLayout, hubs: &[f64], authorities: &mut [f64]) {
+    for (node, authority) in authorities.iter_mut().enumerate() {
+        *authority = weighted_source_sum(layout, node, hubs);
+    }
+    scale_to_unit_sum(authorities);
+}
+
+/// Sums the scores of a node's sources, each scaled by its edge weight
+fn weighted_source_sum(layout: &GraphLayout, node: usize, scores: &[f64]) -> f64 {
+    layout
+        .sources(node)
+        .iter()
+        .zip(layout.source_weights(node))
+        .map(|(&source, &weight)| scores[source as usize] * weight as f64)
+        .sum()
+}
+
+/// Sums the scores of a node's targets, each scaled by its edge weight
+fn weighted_target_sum(layout: &GraphLayout, node: usize, scores: &[f64]) -> f64 {
+    layout
+        .targets(node)
+        .iter()
+        .zip(layout.target_weights(node))
+        .map(|(&target, &weight)| scores[target as usize] * weight as f64)
+        .sum()
+}
+
+fn scale_to_unit_length(values: &mut [f64]) {
+    let length = values.iter().map(|value| value * value).sum::<f64>().sqrt();
+    if length > 0.0 {
+        values.iter_mut().for_each(|value| *value /= length);
+    }
+}
+
+fn scale_to_unit_sum(values: &mut [f64]) {
+    let sum: f64 = values.iter().sum();
+    if sum > 0.0 {
+        values.iter_mut().for_each(|value| *value /= sum);
+    }
+}
+
+/// Applies `step` repeatedly until the L1 distance between successive score
+/// vectors falls below `tolerance` or `max_iter` iterations have run
+fn power_iterate<F>(
+    initial: Vec<f64>,
+    tolerance: f32,
+    max_iter: usize,
+    mut step: F,
+) -> CentralityScores
+where
+    F: FnMut(&[f64], &mut [f64]),
+{
+    let tolerance = tolerance as f64;
+    let mut scores = initial;
+    let mut next_scores = vec![0.0; scores.len()];
+    let mut converged = false;
+    let mut iterations = 0;
+    while iterations < max_iter {
+        iterations += 1;
+        step(&scores, &mut next_scores);
+        let delta: f64 = scores
+            .iter()
+            .zip(next_scores.iter())
+            .map(|(score, next_score)| (score - next_score).abs())
+            .sum();
+        std::mem::swap(&mut scores, &mut next_scores);
+        if delta < tolerance {
+            converged = true;
+            break;
+        }
+    }
+    let values = scores.iter().map(|&score| score as f32).collect();
+    CentralityScores::new(values, converged, iterations)
+}
+
+/// Computes exact betweenness centrality with Brandes' algorithm
+///
+/// Shortest paths are measured in edge lengths, the reciprocals of the edge
+/// weights, so they count hops in unweighted graphs. Runs Dijkstra's
+/// algorithm from every node, so the cost is O(nm log n). Prefer
+/// `betweenness_sampled` for large graphs.
+pub fn betweenness(layout: &GraphLayout, normalized: bool) -> CentralityScores {
+    let node_count = layout.node_count();
+    let mut scratch = BrandesScratch::new(node_count);
+    let mut totals = vec![0.0; node_count];
+    for source in 0..node_count {
+        scratch.accumulate(layout, source, &mut totals);
+    }
+    finish_betweenness(totals, 1.0, normalized, node_count)
+}
+
+/// Approximates betweenness centrality from a random sample of pivot nodes
+///
+/// Dependencies accumulated from each pivot are scaled by `n / samples`,
+/// which gives an unbiased estimate of the exact scores. When `samples` is at
+/// least the node count this is equivalent to `betweenness`.
+pub fn betweenness_sampled(
+    layout: &GraphLayout,
+    samples: usize,
+    seed: u64,
+    normalized: bool,
+) -> CentralityScores {
+    let node_count = layout.node_count();
+    let samples = samples.min(node_count);
+    if samples == node_count {
+        return betweenness(layout, normalized);
+    }
+
+    // Partial Fisher-Yates shuffle to pick pivots without replacement
+    let mut rng = Rng::new(seed);
+    let mut pivots: Vec<usize> = (0..node_count).collect();
+    for index in 0..samples {
+        let swap_index = index + rng.below(node_count - index);
+        pivots.swap(index, swap_index);
+    }
+
+    let mut scratch = BrandesScratch::new(node_count);
+    let mut totals = vec![0.0; node_count];
+    for &source in &pivots[..samples] {
+        scratch.accumulate(layout, source, &mut totals);
+    }
+    let scale = if samples == 0 {
+        0.0
+    } else {
+        node_count as f64 / samples as f64
+    };
+    let mut result = finish_betweenness(totals, scale, normalized, node_count);
+    result.iterations = samples;
+    result
+}
+
+fn finish_betweenness(
+    totals: Vec<f64>,
+    scale: f64,
+    normalized: bool,
+    node_count: usize,
+) -> CentralityScores {
+    // Directed graphs have (n - 1)(n - 2) ordered pairs excluding the node itself
+    let pair_count = node_count.saturating_sub(1) * node_count.saturating_sub(2);
+    let scale = if normalized && pair_count > 0 {
+        scale / pair_count as f64
+    } else {
+        scale
+    };
+    let values = totals.iter().map(|&total| (total * scale) as f32).collect();
+    CentralityScores::new(values, true, node_count)
+}
+
+/// Buffers reused between the single-source passes of Brandes' algorithm
+struct BrandesScratch {
+    search: ShortestPaths,
+    dependencies: Vec<f64>,
+}
+
+impl BrandesScratch {
+    fn new(node_count: usize) -> BrandesScratch {
+        BrandesScratch {
+            search: ShortestPaths::new(node_count),
+            dependencies: vec![0.0; node_count],
+        }
+    }
+
+    fn accumulate(&mut self, layout: &GraphLayout, source: usize, totals: &mut [f64]) {
+        self.search.run(layout, source, Direction::Out);
+        let search = &self.search;
+        for &node in &search.visit_order {
+            self.dependencies[node] = 0.0;
+        }
+
+        // Predecessors on shortest paths are found via the incoming edges,
+        // which avoids storing a predecessor list per node
+        for &node in search.visit_order.iter().rev() {
+            let Some(node_distance) = search.distances[node] else {
+                continue;
+            };
+            let sources = layout.sources(node).iter().zip(layout.source_weights(node));
+            for (&predecessor, &weight) in sources {
+                let predecessor = predecessor as usize;
+                if predecessor == node {
+                    continue;
+                }
+                let (Some(predecessor_distance), Some(length)) =
+                    (search.distances[predecessor], edge_length(weight))
+                else {
+                    continue;
+                };
+                if same_length(predecessor_distance + length, node_distance) {
+                    self.dependencies[predecessor] += (search.path_counts[predecessor]
+                        / search.path_counts[node])
+                        * (1.0 + self.dependencies[node]);
+                }
+            }
+            if node != source {
+                totals[node] += self.dependencies[node];
+            }
+        }
+    }
+}
+
+/// Computes closeness centrality from shortest path distances, measured in
+/// edge lengths as for `betweenness`
+///
+/// Uses the Wasserman-Faust variant, which scales each node's closeness by
+/// the fraction of the graph it can reach so that nodes in small components
+/// don't score highly. Nodes that reach nothing score zero.
+pub fn closeness(layout: &GraphLayout, direction: Direction) -> CentralityScores {
+    let node_count = layout.node_count();
+    let mut search = ShortestPaths::new(node_count);
+    let values = (0..node_count)
+        .map(|node| {
+            search.run(layout, node, direction);
+            let reached = search.visit_order.len() - 1;
+            let distance_sum: f64 = search
+                .visit_order
+                .iter()
+                .filter_map(|&other| search.distances[other])
+                .sum();
+            if reached == 0 || distance_sum == 0.0 {
+                return 0.0;
+            }
+            let reached = reached as f64;
+            let reach_fraction = reached / (node_count - 1) as f64;
+            (reach_fraction * (reached / distance_sum)) as f32
+        })
+        .collect();
+    CentralityScores::new(values, true, node_count)
+}
+
+/// Computes harmonic centrality, the sum of inverse distances to other nodes
+///
+/// Distances are measured in edge lengths as for `betweenness`. Unreachable
+/// nodes contribute zero rather than an infinite distance, so the measure is
+/// well defined for disconnected graphs. When `normalized` is set the sum is
+/// divided by `n - 1`.
+pub fn harmonic(layout: &GraphLayout, direction: Direction, normalized: bool) -> CentralityScores {
+    let node_count = layout.node_count();
+    let mut search = ShortestPaths::new(node_count);
+    let scale = if normalized && node_count > 1 {
+        1.0 / (node_count - 1) as f64
+    } else {
+        1.0
+    };
+    let values = (0..node_count)
+        .map(|node| {
+            search.run(layout, node, direction);
+            let inverse_sum: f64 = search
+                .visit_order
+                .iter()
+                .filter_map(|&other| search.distances[other])
+                .filter(|&distance| distance > 0.0)
+                .map(|distance| 1.0 / distance)
+                .sum();
+            (inverse_sum * scale) as f32
+        })
+        .collect();
+    CentralityScores::new(values, true, node_count)
+}
+
+/// Dijkstra's algorithm over edge lengths, with buffers reused between
+/// source nodes
+struct ShortestPaths {
+    distances: Vec<Option<f64>>,
+    // Number of distinct shortest paths from the source to each node
+    path_counts: Vec<f64>,
+    settled: Vec<bool>,
+    // Reached nodes in order of increasing distance
+    visit_order: Vec<usize>,
+    queue: BinaryHeap<Pending>,
+}
+
+/// A node waiting in the queue, ordered so the nearest is popped first
+#[derive(Debug, PartialEq)]
+struct Pending {
+    distance: f64,
+    node: usize,
+}
+
+impl Eq for Pending {}
+
+impl Ord for Pending {
+    fn cmp(&self, other: &Pending) -> Ordering {
+        other
+            .distance
+            .total_cmp(&self.distance)
+            .then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+impl PartialOrd for Pending {
+    fn partial_cmp(&self, other: &Pending) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl ShortestPaths {
+    fn new(node_count: usize) -> ShortestPaths {
+        ShortestPaths {
+            distances: vec![None; node_count],
+            path_counts: vec![0.0; node_count],
+            settled: vec![false; node_count],
+            visit_order: Vec::with_capacity(node_count),
+            queue: BinaryHeap::new(),
+        }
+    }
+
+    fn run(&mut self, layout: &GraphLayout, source: usize, direction: Direction) {
+        // Only reset the nodes reached by the previous search
+        for &node in &self.visit_order {
+            self.distances[node] = None;
+            self.path_counts[node] = 0.0;
+            self.settled[node] = false;
+        }
+        self.visit_order.clear();
+
+        self.distances[source] = Some(0.0);
+        self.path_counts[source] = 1.0;
+        self.queue.push(Pending {
+            distance: 0.0,
+            node: source,
+        });
+        while let Some(Pending { distance, node }) = self.queue.pop() {
+            // Nodes are queued again when a shorter path is found, leaving
+            // the earlier entries to be skipped
+            if self.settled[node] {
+                continue;
+            }
+            self.settled[node] = true;
+            self.visit_order.push(node);
+            let edges = neighbours(layout, node, direction)
+                .iter()
+                .zip(neighbour_weights(layout, node, direction));
+            for (&neighbour, &weight) in edges {
+                let neighbour = neighbour as usize;
+                let Some(length) = edge_length(weight) else {
+                    continue;
+                };
+                if self.settled[neighbour] {
+                    continue;
+                }
+                let next_distance = distance + length;
+                match self.distances[neighbour] {
+                    Some(known) if same_length(next_distance, known) => {
+                        self.path_counts[neighbour] += self.path_counts[node];
+                    }
+                    Some(known) if known < next_distance => {}
+                    _ => {
+                        self.distances[neighbour] = Some(next_distance);
+                        self.path_counts[neighbour] = self.path_counts[node];
+                        self.queue.push(Pending {
+                            distance: next_distance,
+                            node: neighbour,
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,425 @@
+use super::*;
+
+fn layout_from_edges(node_count: usize, edges: &[(usize, usize)]) -> GraphLayout {
+    let mut layout = GraphLayout::from_locations(node_count, vec![0.0; node_count * 2]);
+    for &(source, target) in edges {
+        layout.add_edges(&[(source, target, 1.0)]).unwrap();
+    }
+    layout
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 1e-4,
+        "expected {} to be close to {}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn test_rank_order_descending_with_ties_by_index() {
+    let scores = [0.1, 0.4, 0.1, 0.4, 0.0];
+
+    assert_eq!(rank_order(&scores), vec![1, 3, 0, 2, 4]);
+}
+
+#[test]
+fn test_pagerank_empty_graph() {
+    let layout = layout_from_edges(0, &[]);
+
+    let result = pagerank(&layout, 0.85, 1e-6, 100);
+
+    assert!(result.values().is_empty());
+    assert!(result.converged);
+}
+
+#[test]
+fn test_pagerank_cycle_is_uniform() {
+    let layout = layout_from_edges(3, &[(0, 1), (1, 2), (2, 0)]);
+
+    let result = pagerank(&layout, 0.85, 1e-6, 100);
+
+    assert!(result.converged);
+    for &score in result.values() {
+        assert_close(score, 1.0 / 3.0);
+    }
+}
+
+#[test]
+fn test_pagerank_star_ranks_hub_first() {
+    let layout = layout_from_edges(4, &[(1, 0), (2, 0), (3, 0)]);
+
+    let result = pagerank(&layout, 0.85, 1e-6, 100);
+
+    assert!(result.converged);
+    assert_eq!(rank_order(result.values())[0], 0);
+    let total: f32 = result.values().iter().sum();
+    assert_close(total, 1.0);
+}
+
+#[test]
+fn test_pagerank_dangling_node_mass_is_conserved() {
+    // Node 1 is dangling; its rank should be redistributed rather than lost
+    let layout = layout_from_edges(2, &[(0, 1)]);
+
+    let result = pagerank(&layout, 0.85, 1e-8, 200);
+
+    let total: f32 = result.values().iter().sum();
+    assert_close(total, 1.0);
+    // Stationary solution of the Google matrix for this graph
+    assert_close(result.values()[0], 1.0 / 2.85);
+    assert_close(result.values()[1], 1.85 / 2.85);
+}
+
+#[test]
+fn test_pagerank_stops_at_max_iter() {
+    let layout = layout_from_edges(3, &[(0, 1), (1, 2)]);
+
+    let result = pagerank(&layout, 0.85, 0.0, 5);
+
+    assert!(!result.converged);
+    assert_eq!(result.iterations, 5);
+}
+
+#[test]
+fn test_betweenness_directed_path() {
+    let layout = layout_from_edges(4, &[(0, 1), (1, 2), (2, 3)]);
+
+    let result = betweenness(&layout, false);
+
+    assert_eq!(result.values(), &[0.0, 2.0, 2.0, 0.0]);
+}
+
+#[test]
+fn test_betweenness_splits_between_equal_paths() {
+    // Two shortest paths from 0 to 3, one through 1 and one through 2
+    let layout = layout_from_edges(4, &[(0, 1), (0, 2), (1, 3), (2, 3)]);
+
+    let result = betweenness(&layout, false);
+
+    assert_eq!(result.values(), &[0.0, 0.5, 0.5, 0.0]);
+}
+
+#[test]
+fn test_betweenness_normalized() {
+    let layout = layout_from_edges(3, &[(0, 1), (1, 2)]);
+
+    let result = betweenness(&layout, true);
+
+    assert_eq!(result.values(), &[0.0, 0.5, 0.0]);
+}
+
+#[test]
+fn test_betweenness_sampled_with_all_pivots_is_exact() {
+    let edges = [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 2)];
+    let layout = layout_from_edges(5, &edges);
+
+    let exact = betweenness(&layout, false);
+    let sampled = betweenness_sampled(&layout, 10, 7, false);
+
+    assert_eq!(exact.values(), sampled.values());
+}
+
+#[test]
+fn test_betweenness_sampled_is_reproducible_and_scaled() {
+    let edges = [(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 0)];
+    let layout = layout_from_edges(6, &edges);
+
+    let first = betweenness_sampled(&layout, 3, 42, false);
+    let second = betweenness_sampled(&layout, 3, 42, false);
+
+    assert_eq!(first.values(), second.values());
+    assert_eq!(first.iterations, 3);
+    // Every node of a directed cycle has the same exact betweenness, so the
+    // estimates should sum to the exact total
+    let exact_total: f32 = betweenness(&layout, false).values().iter().sum();
+    let sampled_total: f32 = first.values().iter().sum();
+    assert_close(sampled_total, exact_total);
+}
+
+#[test]
+fn test_closeness_directed_path_out() {
+    let layout = layout_from_edges(3, &[(0, 1), (1, 2)]);
+
+    let result = closeness(&layout, Direction::Out);
+
+    // Node 0 reaches both others with distances 1 and 2
+    assert_close(result.values()[0], 2.0 / 3.0);
+    // Node 1 reaches only node 2, half of the graph
+    assert_close(result.values()[1], 0.5);
+    assert_close(result.values()[2], 0.0);
+}
+
+#[test]
+fn test_closeness_directed_path_in() {
+    let layout = layout_from_edges(3, &[(0, 1), (1, 2)]);
+
+    let result = closeness(&layout, Direction::In);
+
+    assert_close(result.values()[0], 0.0);
+    assert_close(result.values()[1], 0.5);
+    assert_close(result.values()[2], 2.0 / 3.0);
+}
+
+#[test]
+fn test_harmonic_disconnected_graph() {
+    // Two separate components: 0 -> 1 -> 2 and 3 -> 4
+    let layout = layout_from_edges(5, &[(0, 1), (1, 2), (3, 4)]);
+
+    let result = harmonic(&layout, Direction::Out, false);
+
+    assert_close(result.values()[0], 1.5);
+    assert_close(result.values()[1], 1.0);
+    assert_close(result.values()[2], 0.0);
+    assert_close(result.values()[3], 1.0);
+    assert_close(result.values()[4], 0.0);
+}
+
+#[test]
+fn test_harmonic_normalized_in_direction() {
+    let layout = layout_from_edges(3, &[(0, 2), (1, 2)]);
+
+    let result = harmonic(&layout, Direction::In, true);
+
+    assert_close(result.values()[0], 0.0);
+    assert_close(result.values()[1], 0.0);
+    assert_close(result.values()[2], 1.0);
+}
+
+#[test]
+fn test_eigenvector_cycle_converges_to_uniform() {
+    let layout = layout_from_edges(3, &[(0, 1), (1, 2), (2, 0)]);
+
+    let result = eigenvector(&layout, 1e-6, 100);
+
+    assert!(result.converged);
+    for &score in result.values() {
+        assert_close(score, 1.0 / 3.0_f32.sqrt());
+    }
+}
+
+#[test]
+fn test_eigenvector_favours_node_with_most_sources() {
+    // Node 0 is pointed at by both other nodes of a strongly connected graph
+    let layout = layout_from_edges(3, &[(0, 1), (1, 0), (2, 0), (0, 2)]);
+
+    let result = eigenvector(&layout, 1e-6, 200);
+
+    assert!(result.converged);
+    assert_eq!(rank_order(result.values())[0], 0);
+    assert_close(result.values()[1], result.values()[2]);
+}
+
+#[test]
+fn test_katz_directed_path() {
+    let layout = layout_from_edges(3, &[(0, 1), (1, 2)]);
+
+    let result = katz(&layout, 0.5, 1.0, 1e-6, 100, false);
+
+    assert!(result.converged);
+    assert_close(result.values()[0], 1.0);
+    assert_close(result.values()[1], 1.5);
+    assert_close(result.values()[2], 1.75);
+}
+
+#[test]
+fn test_katz_reports_divergence() {
+    // Alpha above 1 / lambda_max of a cycle (lambda_max = 1) never settles
+    let layout = layout_from_edges(2, &[(0, 1), (1, 0)]);
+
+    let result = katz(&layout, 1.5, 1.0, 1e-6, 20, false);
+
+    assert!(!result.converged);
+    assert_eq!(result.iterations, 20);
+}
+
+#[test]
+fn test_hits_star() {
+    // Nodes 1 and 2 both point at 0 and 3
+    let layout = layout_from_edges(4, &[(1, 0), (1, 3), (2, 0), (2, 3)]);
+
+    let result = hits(&layout, 1e-8, 100);
+
+    assert!(result.hubs.converged);
+    let hubs = result.hubs.values();
+    let authorities = result.authorities.values();
+    assert_close(hubs[1], 0.5);
+    assert_close(hubs[2], 0.5);
+    assert_close(hubs[0], 0.0);
+    assert_close(authorities[0], 0.5);
+    assert_close(authorities[3], 0.5);
+    assert_close(authorities[1], 0.0);
+}
+
+#[test]
+fn test_personalized_pagerank_favours_seed_neighbourhood() {
+    // Two triangles joined by the edge 2 -> 3
+    let edges = [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 5), (5, 3)];
+    let layout = layout_from_edges(6, &edges);
+
+    let result = personalized_pagerank(&layout, &[0], 0.85, 1e-8, 200);
+
+    assert!(result.converged);
+    let total: f32 = result.values().iter().sum();
+    assert_close(total, 1.0);
+    assert_eq!(top_nodes(result.values(), 1, &[0]), vec![1]);
+    // Nodes in the seed's triangle outrank those further along the cycle
+    assert!(result.values()[2] > result.values()[4]);
+    assert!(result.values()[2] > result.values()[5]);
+}
+
+#[test]
+fn test_personalized_pagerank_unreachable_nodes_score_zero() {
+    let layout = layout_from_edges(4, &[(0, 1), (2, 3)]);
+
+    let result = personalized_pagerank(&layout, &[0], 0.85, 1e-8, 200);
+
+    assert_close(result.values()[2], 0.0);
+    assert_close(result.values()[3], 0.0);
+    assert!(result.values()[1] > 0.0);
+}
+
+#[test]
+fn test_personalized_pagerank_with_every_node_seeded_matches_pagerank() {
+    let edges = [(0, 1), (1, 2), (2, 0), (2, 3)];
+    let layout = layout_from_edges(4, &edges);
+
+    let global = pagerank(&layout, 0.85, 1e-8, 200);
+    let personalized = personalized_pagerank(&layout, &[0, 1, 2, 3], 0.85, 1e-8, 200);
+
+    for (&expected, &actual) in global.values().iter().zip(personalized.values()) {
+        assert_close(actual, expected);
+    }
+}
+
+#[test]
+fn test_top_nodes_limits_count() {
+    let scores = [0.5, 0.1, 0.3, 0.2];
+
+    assert_eq!(top_nodes(&scores, 2, &[]), vec![0, 2]);
+    assert_eq!(top_nodes(&scores, 10, &[0, 2]), vec![3, 1]);
+}
+
+#[test]
+fn test_incremental_pagerank_matches_full_computation() {
+    let edges = [
+        (0, 1, 1.0),
+        (1, 2, 1.0),
+        (2, 0, 1.0),
+        (2, 3, 1.0),
+        (3, 1, 1.0),
+    ];
+    let mut layout = layout_from_edges(4, &[]);
+    let mut incremental = IncrementalPageRank::new(&layout, 0.85, 1e-7, 10);
+
+    for chunk in edges.chunks(2) {
+        layout.add_edges(chunk).unwrap();
+        incremental.refine(&layout);
+    }
+    while !incremental.scores().converged {
+        incremental.refine(&layout);
+    }
+
+    let full = pagerank(&layout, 0.85, 1e-7, 200);
+    for (&expected, &actual) in full.values().iter().zip(incremental.scores().values()) {
+        assert_close(actual, expected);
+    }
+}
+
+#[test]
+fn test_incremental_pagerank_counts_iterations_across_chunks() {
+    let mut layout = layout_from_edges(3, &[(0, 1)]);
+    let mut incremental = IncrementalPageRank::new(&layout, 0.85, 0.0, 3);
+
+    incremental.refine(&layout);
+    layout.add_edges(&[(1, 2, 1.0)]).unwrap();
+    incremental.refine(&layout);
+
+    assert_eq!(incremental.scores().iterations, 6);
+    assert!(!incremental.scores().converged);
+}
+
+#[test]
+fn test_pagerank_follows_edge_weights() {
+    // Node 0 sends three times as much weight to node 1 as to node 2
+    let edges = [(0, 1, 3.0), (0, 2, 1.0), (1, 0, 1.0), (2, 0, 1.0)];
+    let layout = GraphLayout::from_weighted_edges(3, &edges);
+
+    let result = pagerank(&layout, 0.85, 1e-8, 200);
+
+    let values = result.values();
+    assert!(values[1] > values[2]);
+    // The shares above the teleport baseline split in the ratio of the weights
+    let baseline = 0.15 / 3.0;
+    assert_close((values[1] - baseline) / (values[2] - baseline), 3.0);
+}
+
+#[test]
+fn test_unit_weights_match_unweighted() {
+    let edges = [(0, 1), (1, 2), (2, 0), (2, 1)];
+    let unweighted = layout_from_edges(3, &edges);
+    let weighted_edges: Vec<(usize, usize, f32)> = edges
+        .iter()
+        .map(|&(source, target)| (source, target, 1.0))
+        .collect();
+    let weighted = GraphLayout::from_weighted_edges(3, &weighted_edges);
+
+    let expected = eigenvector(&unweighted, 1e-6, 200);
+    let actual = eigenvector(&weighted, 1e-6, 200);
+
+    assert_eq!(expected.values(), actual.values());
+}
+
+#[test]
+fn test_hits_weights_authorities() {
+    let layout = GraphLayout::from_weighted_edges(3, &[(0, 1, 3.0), (0, 2, 1.0)]);
+
+    let result = hits(&layout, 1e-8, 100);
+
+    assert_close(result.authorities.values()[1], 0.75);
+    assert_close(result.authorities.values()[2], 0.25);
+}
+
+#[test]
+fn test_betweenness_prefers_heavier_detour() {
+    // The detour through node 1 has total length 0.5, shorter than the
+    // direct edge of length 1
+    let edges = [(0, 1, 4.0), (1, 2, 4.0), (0, 2, 1.0)];
+    let layout = GraphLayout::from_weighted_edges(3, &edges);
+
+    let result = betweenness(&layout, false);
+
+    assert_eq!(result.values(), &[0.0, 1.0, 0.0]);
+}
+
+#[test]
+fn test_betweenness_splits_equally_weighted_paths() {
+    // Both paths to node 3 have length 1, one rounding differently in f32
+    let edges = [(0, 1, 2.0), (1, 3, 2.0), (0, 2, 4.0), (2, 3, 4.0 / 3.0)];
+    let layout = GraphLayout::from_weighted_edges(4, &edges);
+
+    let result = betweenness(&layout, false);
+
+    assert_close(result.values()[1], 0.5);
+    assert_close(result.values()[2], 0.5);
+}
+
+#[test]
+fn test_closeness_uses_edge_lengths() {
+    let layout = GraphLayout::from_weighted_edges(3, &[(0, 1, 2.0), (0, 2, 0.5)]);
+
+    let result = closeness(&layout, Direction::Out);
+
+    // Distances of 0.5 and 2 to the other two nodes
+    assert_close(result.values()[0], 2.0 / 2.5);
+}
+
+#[test]
+fn test_harmonic_skips_zero_weight_edges() {
+    let layout = GraphLayout::from_weighted_edges(3, &[(0, 1, 0.5), (0, 2, 0.0)]);
+
+    let result = harmonic(&layout, Direction::Out, false);
+
+    assert_close(result.values()[0], 0.5);
+}
@@ -0,0 +1,300 @@
+//! Writes the graph, with its current node locations and any centrality
+//! scores, as GraphML or CSV
+//!
+//! Nodes are identified by their index, which stays unique even when
+//! usernames repeat. Usernames and ranks are written alongside as node data,
+//! so exports can be imported again.
+
+use crate::{nodes, GraphLayout};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+
+/// Named per-node scores to include in an export
+pub type NamedScores = [(String, Vec<f32>)];
+
+fn node_label(nodes: &nodes::NodeStore, index: usize) -> &str {
+    nodes.get(index).map_or("", |node| node.name.as_str())
+}
+
+fn node_username(nodes: &nodes::NodeStore, index: usize) -> &str {
+    nodes.get(index).map_or("", |node| node.username.as_str())
+}
+
+fn node_rank(nodes: &nodes::NodeStore, index: usize) -> String {
+    nodes
+        .get(index)
+        .map(|node| node.rank.to_string())
+        .unwrap_or_default()
+}
+
+/// The GraphML type of a value, or `None` for nulls, which fit any type
+fn value_type(value: &Value) -> Option<&'static str> {
+    match value {
+        Value::Null => None,
+        Value::Bool(_) => Some("boolean"),
+        Value::Number(number) if number.is_i64() => Some("long"),
+        Value::Number(_) => Some("double"),
+        _ => Some("string"),
+    }
+}
+
+/// Every attribute name with a GraphML type that all its values can be read
+/// as, which is string when their types disagree
+fn attribute_types(nodes: &nodes::NodeStore) -> BTreeMap<&str, &'static str> {
+    // None until a value other than null is seen
+    let mut types: BTreeMap<&str, Option<&'static str>> = BTreeMap::new();
+    for index in 0..nodes.len() {
+        for (name, value) in &nodes.get(index).unwrap().attributes {
+            let kind = types.entry(name.as_str()).or_insert(None);
+            *kind = match (*kind, value_type(value)) {
+                (kind, None) => kind,
+                (None, value_kind) => value_kind,
+                (Some(kind), Some(value_kind)) if kind == value_kind => Some(kind),
+                (Some("long"), Some("double")) | (Some("double"), Some("long")) => Some("double"),
+                _ => Some("string"),
+            };
+        }
+    }
+    types
+        .into_iter()
+        .map(|(name, kind)| (name, kind.unwrap_or("string")))
+        .collect()
+}
+
+/// Gives each name a suffix where needed to keep it apart from the reserved
+/// names and the names before it
+fn unique_names<'a>(reserved: &[&str], names: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut taken: HashSet<String> = reserved.iter().map(|name| name.to_string()).collect();
+    names
+        .map(|name| {
+            let mut unique = name.to_string();
+            let mut suffix = 2;
+            while taken.contains(&unique) {
+                unique = format!("{}_{}", name, suffix);
+                suffix += 1;
+            }
+            taken.insert(unique.clone());
+            unique
+        })
+        .collect()
+}
+
+fn attribute_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        value => value.to_string(),
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            character => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+/// Built-in node data, which attributes and scores must not be named after
+const GRAPHML_NODE_KEYS: [(&str, &str); 5] = [
+    ("label", "string"),
+    ("username", "string"),
+    ("rank", "long"),
+    ("x", "float"),
+    ("y", "float"),
+];
+
+pub fn to_graphml(layout: &GraphLayout, nodes: &nodes::NodeStore, scores: &NamedScores) -> String {
+    let attribute_types = attribute_types(nodes);
+    let reserved: Vec<&str> = GRAPHML_NODE_KEYS.iter().map(|&(name, _)| name).collect();
+    let extra_names = unique_names(
+        &reserved,
+        attribute_types
+            .keys()
+            .copied()
+            .chain(scores.iter().map(|(name, _)| name.as_str())),
+    );
+    let extra_types = attribute_types
+        .values()
+        .copied()
+        .chain(scores.iter().map(|_| "double"));
+    let mut keys: Vec<(&str, &str)> = GRAPHML_NODE_KEYS.to_vec();
+    keys.extend(extra_names.iter().map(String::as_str).zip(extra_types));
+
+    let mut graphml = String::new();
+    graphml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    graphml.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    for (key_index, (name, kind)) in keys.iter().enumerate() {
+        writeln!(
+            graphml,
+            "  <key id=\"d{}\" for=\"node\" attr.name=\"{}\" attr.type=\"{}\"/>",
+            key_index,
+            escape_xml(name),
+            kind
+        )
+        .unwrap();
+    }
+    let weight_key = keys.len();
+    writeln!(
+        graphml,
+        "  <key id=\"d{}\" for=\"edge\" attr.name=\"weight\" attr.type=\"double\"/>",
+        weight_key
+    )
+    .unwrap();
+    graphml.push_str("  <graph edgedefault=\"directed\">\n");
+
+    let attribute_offset = GRAPHML_NODE_KEYS.len();
+    let score_offset = attribute_offset + attribute_types.len();
+    for index in 0..layout.node_count() {
+        let location = layout.node_location(index);
+        writeln!(graphml, "    <node id=\"{}\">", index).unwrap();
+        let mut data = Vec::new();
+        if let Some(node) = nodes.get(index) {
+            if !node.name.is_empty() {
+                data.push((0, escape_xml(&node.name)));
+            }
+            data.push((1, escape_xml(&node.username)));
+            data.push((2, node.rank.to_string()));
+        }
+        data.push((3, location.x.to_string()));
+        data.push((4, location.y.to_string()));
+        if let Some(node) = nodes.get(index) {
+            for (offset, name) in attribute_types.keys().enumerate() {
+                if let Some(value) = node.attributes.get(*name).filter(|value| !value.is_null()) {
+                    data.push((
+                        attribute_offset + offset,
+                        escape_xml(&attribute_text(value)),
+                    ));
+                }
+            }
+        }
+        for (offset, (_, values)) in scores.iter().enumerate() {
+            if let Some(value) = values.get(index) {
+                data.push((score_offset + offset, value.to_string()));
+            }
+        }
+        for (key_index, text) in data {
+            writeln!(
+                graphml,
+                "      <data key=\"d{}\">{}</data>",
+                key_index, text
+            )
+            .unwrap();
+        }
+        graphml.push_str("    </node>\n");
+    }
+    for source in 0..layout.node_count() {
+        let edges = layout
+            .targets(source)
+            .iter()
+            .zip(layout.target_weights(source));
+        for (&target, weight) in edges {
+            writeln!(
+                graphml,
+                "    <edge source=\"{}\" target=\"{}\">",
+                source, target
+            )
+            .unwrap();
+            writeln!(
+                graphml,
+                "      <data key=\"d{}\">{}</data>",
+                weight_key, weight
+            )
+            .unwrap();
+            graphml.push_str("    </edge>\n");
+        }
+    }
+    graphml.push_str("  </graph>\n</graphml>\n");
+    graphml
+}
+
+fn csv_text(writer: csv::Writer<Vec<u8>>) -> String {
+    let bytes = writer
+        .into_inner()
+        .expect("Writing CSV to memory should succeed");
+    String::from_utf8(bytes).expect("CSV of strings should be UTF-8")
+}
+
+/// Built-in node list columns, which attributes and scores must not be
+/// named after
+const CSV_NODE_COLUMNS: [&str; 6] = ["id", "label", "username", "rank", "x", "y"];
+
+/// Writes an id, label, username, rank, x and y column for each node,
+/// followed by its attributes and scores
+pub fn nodes_to_csv(
+    layout: &GraphLayout,
+    nodes: &nodes::NodeStore,
+    scores: &NamedScores,
+) -> String {
+    let attribute_names: Vec<&str> = attribute_types(nodes).into_keys().collect();
+    let extra_names = unique_names(
+        &CSV_NODE_COLUMNS,
+        attribute_names
+            .iter()
+            .copied()
+            .chain(scores.iter().map(|(name, _)| name.as_str())),
+    );
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let mut headers: Vec<&str> = CSV_NODE_COLUMNS.to_vec();
+    headers.extend(extra_names.iter().map(String::as_str));
+    writer
+        .write_record(&headers)
+        .expect("Writing CSV to memory should succeed");
+
+    for index in 0..layout.node_count() {
+        let location = layout.node_location(index);
+        let mut record = vec![
+            index.to_string(),
+            node_label(nodes, index).to_string(),
+            node_username(nodes, index).to_string(),
+            node_rank(nodes, index),
+            location.x.to_string(),
+            location.y.to_string(),
+        ];
+        let node = nodes.get(index);
+        for name in &attribute_names {
+            let value = node
+                .and_then(|node| node.attributes.get(*name))
+                .filter(|value| !value.is_null());
+            record.push(value.map(attribute_text).unwrap_or_default());
+        }
+        for (_, values) in scores {
+            let value = values.get(index).map(|value| value.to_string());
+            record.push(value.unwrap_or_default());
+        }
+        writer
+            .write_record(&record)
+            .expect("Writing CSV to memory should succeed");
+    }
+    csv_text(writer)
+}
+
+/// Writes a source, target and weight row for each edge
+pub fn edges_to_csv(layout: &GraphLayout) -> String {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(["source", "target", "weight"])
+        .expect("Writing CSV to memory should succeed");
+    for source in 0..layout.node_count() {
+        let edges = layout
+            .targets(source)
+            .iter()
+            .zip(layout.target_weights(source));
+        for (&target, weight) in edges {
+            writer
+                .write_record([source.to_string(), target.to_string(), weight.to_string()])
+                .expect("Writing CSV to memory should succeed");
+        }
+    }
+    csv_text(writer)
+}
+
+#[cfg(test)]
+mod tests;
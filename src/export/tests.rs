@@ -0,0 +1,146 @@
+use super::*;
+use crate::geometry::Vector2;
+use crate::import;
+
+fn sample_nodes() -> nodes::NodeStore {
+    nodes::NodeStore::from_json(
+        r#"[
+            {"username": "alice", "name": "Alice & Co", "rank": 1, "x": 0.0, "y": 0.0, "followers": 12},
+            {"username": "bob", "rank": 2, "x": 0.0, "y": 0.0, "city": "Leeds, UK"}
+        ]"#,
+    )
+    .unwrap()
+}
+
+fn sample_layout() -> GraphLayout {
+    let mut layout = GraphLayout::from_weighted_edges(2, &[(0, 1, 2.5), (1, 0, 1.0)]);
+    layout.set_node_location(1, Vector2::new(3.0, -4.0));
+    layout
+}
+
+#[test]
+fn test_escape_xml() {
+    assert_eq!(
+        escape_xml("<a href=\"x\">&'"),
+        "&lt;a href=&quot;x&quot;&gt;&amp;&apos;"
+    );
+}
+
+#[test]
+fn test_graphml_round_trip() {
+    let scores = vec![("pagerank".to_string(), vec![0.75, 0.25])];
+
+    let graphml = to_graphml(&sample_layout(), &sample_nodes(), &scores);
+    let imported = import::from_graphml(&graphml).unwrap();
+
+    let layout = imported.layout;
+    assert_eq!(layout.targets(0), &[1]);
+    assert_eq!(layout.target_weights(0), &[2.5]);
+    assert_eq!(layout.targets(1), &[0]);
+    assert_eq!(layout.node_location(1), Vector2::new(3.0, -4.0));
+    let alice = imported.nodes.get(0).unwrap();
+    assert_eq!(alice.username, "alice");
+    assert_eq!(alice.display_name(), "Alice & Co");
+    assert_eq!(alice.attributes.get("followers"), Some(&Value::from(12)));
+    assert_eq!(alice.attributes.get("pagerank"), Some(&Value::from(0.75)));
+    assert_eq!(alice.rank, 1);
+    assert_eq!(imported.nodes.get(1).unwrap().display_name(), "bob");
+    assert_eq!(imported.nodes.get(1).unwrap().rank, 2);
+}
+
+#[test]
+fn test_graphml_duplicate_usernames_keep_nodes_apart() {
+    let nodes = nodes::NodeStore::from_json(
+        r#"[
+            {"username": "sam", "rank": 1, "x": 0.0, "y": 0.0},
+            {"username": "sam", "rank": 2, "x": 0.0, "y": 0.0}
+        ]"#,
+    )
+    .unwrap();
+
+    let graphml = to_graphml(&sample_layout(), &nodes, &[]);
+    let imported = import::from_graphml(&graphml).unwrap();
+
+    assert_eq!(imported.layout.node_count(), 2);
+    assert_eq!(imported.layout.targets(0), &[1]);
+    assert_eq!(imported.nodes.get(1).unwrap().username, "sam");
+}
+
+#[test]
+fn test_graphml_names_colliding_with_built_in_keys() {
+    let scores = vec![
+        ("x".to_string(), vec![0.5, 0.5]),
+        ("followers".to_string(), vec![0.25, 0.75]),
+    ];
+
+    let graphml = to_graphml(&sample_layout(), &sample_nodes(), &scores);
+    let imported = import::from_graphml(&graphml).unwrap();
+
+    assert!(graphml.contains("attr.name=\"x_2\""));
+    assert!(graphml.contains("attr.name=\"followers_2\""));
+    let alice = imported.nodes.get(0).unwrap();
+    assert_eq!(alice.attributes.get("followers"), Some(&Value::from(12)));
+    assert_eq!(
+        alice.attributes.get("followers_2"),
+        Some(&Value::from(0.25))
+    );
+    assert_eq!(imported.layout.node_location(1), Vector2::new(3.0, -4.0));
+}
+
+#[test]
+fn test_attribute_types_fall_back_to_string() {
+    let nodes = nodes::NodeStore::from_json(
+        r#"[
+            {"username": "a", "rank": 1, "x": 0.0, "y": 0.0, "size": 1, "code": null, "tag": 5},
+            {"username": "b", "rank": 2, "x": 0.0, "y": 0.0, "size": 1.5, "code": true, "tag": "five"}
+        ]"#,
+    )
+    .unwrap();
+
+    let types = attribute_types(&nodes);
+
+    assert_eq!(types.get("size"), Some(&"double"));
+    assert_eq!(types.get("code"), Some(&"boolean"));
+    assert_eq!(types.get("tag"), Some(&"string"));
+}
+
+#[test]
+fn test_graphml_without_node_metadata() {
+    let graphml = to_graphml(&sample_layout(), &nodes::NodeStore::default(), &[]);
+
+    assert!(graphml.contains("<node id=\"1\">"));
+    assert!(graphml.contains("<edge source=\"0\" target=\"1\">"));
+}
+
+#[test]
+fn test_nodes_csv() {
+    let scores = vec![("pagerank".to_string(), vec![0.75, 0.25])];
+
+    let csv = nodes_to_csv(&sample_layout(), &sample_nodes(), &scores);
+
+    assert_eq!(
+        csv,
+        "id,label,username,rank,x,y,city,followers,pagerank\n\
+         0,Alice & Co,alice,1,0,0,,12,0.75\n\
+         1,,bob,2,3,-4,\"Leeds, UK\",,0.25\n"
+    );
+}
+
+#[test]
+fn test_csv_round_trip() {
+    let nodes_csv = nodes_to_csv(&sample_layout(), &sample_nodes(), &[]);
+    let edges_csv = edges_to_csv(&sample_layout());
+
+    let imported = import::from_csv(&edges_csv, Some(&nodes_csv)).unwrap();
+
+    assert_eq!(edges_csv, "source,target,weight\n0,1,2.5\n1,0,1\n");
+    assert_eq!(imported.nodes.get(0).unwrap().username, "alice");
+    assert_eq!(imported.nodes.get(1).unwrap().rank, 2);
+    assert_eq!(imported.layout.targets(0), &[1]);
+    assert_eq!(imported.layout.target_weights(0), &[2.5]);
+    assert_eq!(imported.layout.node_location(1), Vector2::new(3.0, -4.0));
+    assert_eq!(
+        imported.nodes.get(1).unwrap().attributes.get("city"),
+        Some(&Value::from("Leeds, UK"))
+    );
+}
@@ -0,0 +1,186 @@
+use crate::geometry::Vector2;
+use crate::quadtree::QuadTree;
+use crate::stats::{self, DegreeKind};
+use crate::GraphLayout;
+
+const DEFAULT_REPULSION: f32 = 10.0;
+const DEFAULT_GRAVITY: f32 = 1.0;
+const DEFAULT_JITTER_TOLERANCE: f32 = 1.0;
+const DEFAULT_MAX_DISPLACEMENT: f32 = 10.0;
+const DEFAULT_THETA: f32 = 1.2;
+const GOLDEN_ANGLE: f32 = 2.399_963;
+
+/// ForceAtlas2-style force-directed layout
+///
+/// Nodes repel each other in proportion to their degrees, edges pull their
+/// ends together linearly in proportion to their weight and gravity draws
+/// every node towards the origin. The step size adapts to how much nodes
+/// oscillate ("swing") versus how consistently they move ("traction"), as
+/// described by Jacomy et al. (2014). Repulsion is approximated with a
+/// Barnes-Hut quadtree so each step costs O(n log n) rather than O(n²).
+pub struct ForceLayout {
+    repulsion: f32,
+    gravity: f32,
+    jitter_tolerance: f32,
+    max_displacement: f32,
+    theta: f32,
+    speed: f32,
+    previous_forces: Vec<Vector2>,
+}
+
+impl Default for ForceLayout {
+    fn default() -> ForceLayout {
+        ForceLayout::new(
+            DEFAULT_REPULSION,
+            DEFAULT_GRAVITY,
+            DEFAULT_JITTER_TOLERANCE,
+            DEFAULT_MAX_DISPLACEMENT,
+            DEFAULT_THETA,
+        )
+    }
+}
+
+impl ForceLayout {
+    pub fn new(
+        repulsion: f32,
+        gravity: f32,
+        jitter_tolerance: f32,
+        max_displacement: f32,
+        theta: f32,
+    ) -> ForceLayout {
+        ForceLayout {
+            repulsion,
+            gravity,
+            jitter_tolerance,
+            max_displacement,
+            theta,
+            speed: 1.0,
+            previous_forces: Vec::new(),
+        }
+    }
+
+    /// Forgets the adaptive speed state, e.g. after nodes have been moved
+    pub fn reset(&mut self) {
+        self.speed = 1.0;
+        self.previous_forces.clear();
+    }
+
+    pub fn step(&mut self, layout: &mut GraphLayout) {
+        let node_count = layout.node_count();
+        if node_count == 0 {
+            return;
+        }
+        let masses: Vec<f32> = stats::degrees(layout, DegreeKind::Total)
+            .iter()
+            .map(|&degree| degree as f32 + 1.0)
+            .collect();
+        let locations: Vec<Vector2> = (0..node_count)
+            .map(|node| layout.node_location(node))
+            .collect();
+
+        let mut forces = vec![Vector2::new(0.0, 0.0); node_count];
+        self.apply_repulsion(layout, &locations, &masses, &mut forces);
+        apply_attraction(layout, &locations, &mut forces);
+        self.apply_gravity(&locations, &masses, &mut forces);
+
+        if self.previous_forces.len() != node_count {
+            self.previous_forces = vec![Vector2::new(0.0, 0.0); node_count];
+        }
+        let swings: Vec<f32> = forces
+            .iter()
+            .zip(self.previous_forces.iter())
+            .zip(masses.iter())
+            .map(|((&force, &previous), &mass)| mass * (force - previous).magnitude())
+            .collect();
+        self.adapt_speed(&forces, &swings, &masses);
+
+        let moves: Vec<(usize, Vector2)> = (0..node_count)
+            .filter(|&node| !layout.is_pinned(node))
+            .map(|node| {
+                let node_speed = self.speed / (1.0 + (self.speed * swings[node]).sqrt());
+                let mut displacement = forces[node] * node_speed;
+                let distance = displacement.magnitude();
+                if distance > self.max_displacement {
+                    displacement = displacement * (self.max_displacement / distance);
+                }
+                (node, locations[node] + displacement)
+            })
+            .collect();
+        layout.move_nodes(moves);
+        self.previous_forces = forces;
+    }
+
+    fn apply_repulsion(
+        &self,
+        layout: &GraphLayout,
+        locations: &[Vector2],
+        masses: &[f32],
+        forces: &mut [Vector2],
+    ) {
+        let tree = QuadTree::new(layout.node_locations(), masses);
+        for (node, &location) in locations.iter().enumerate() {
+            let mut force = Vector2::new(0.0, 0.0);
+            tree.visit_masses(location, self.theta, node, |center, mass| {
+                let delta = location - center;
+                let distance_squared = delta.x * delta.x + delta.y * delta.y;
+                if distance_squared > 0.0 {
+                    // Magnitude k * m1 * m2 / d along the unit vector delta / d
+                    force += delta * (self.repulsion * masses[node] * mass / distance_squared);
+                }
+            });
+            forces[node] += force;
+        }
+    }
+
+    fn apply_gravity(&self, locations: &[Vector2], masses: &[f32], forces: &mut [Vector2]) {
+        for ((location, &mass), force) in locations.iter().zip(masses).zip(forces.iter_mut()) {
+            if let Some(direction) = location.unit() {
+                *force -= direction * (self.gravity * mass);
+            }
+        }
+    }
+
+    fn adapt_speed(&mut self, forces: &[Vector2], swings: &[f32], masses: &[f32]) {
+        let total_swing: f32 = swings.iter().sum();
+        let total_traction: f32 = forces
+            .iter()
+            .zip(self.previous_forces.iter())
+            .zip(masses.iter())
+            .map(|((&force, &previous), &mass)| mass * (force + previous).magnitude() / 2.0)
+            .sum();
+        if total_swing == 0.0 {
+            return;
+        }
+        let target_speed = self.jitter_tolerance * total_traction / total_swing;
+        // Limit how quickly the speed may rise to avoid sudden instability
+        self.speed += (target_speed - self.speed).min(self.speed * 0.5);
+    }
+}
+
+fn apply_attraction(layout: &GraphLayout, locations: &[Vector2], forces: &mut [Vector2]) {
+    for source in 0..layout.node_count() {
+        let targets = layout.targets(source);
+        for (&target, &weight) in targets.iter().zip(layout.target_weights(source)) {
+            let target = target as usize;
+            let delta = (locations[target] - locations[source]) * weight;
+            forces[source] += delta;
+            forces[target] -= delta;
+        }
+    }
+}
+
+/// Interleaved x, y locations on a sunflower spiral with roughly `spacing`
+/// between neighbouring nodes
+pub fn spiral_locations(node_count: usize, spacing: f32) -> Vec<f32> {
+    let mut locations = Vec::with_capacity(node_count * 2);
+    for node in 0..node_count {
+        let radius = spacing * (node as f32).sqrt();
+        let angle = node as f32 * GOLDEN_ANGLE;
+        locations.push(radius * angle.cos());
+        locations.push(radius * angle.sin());
+    }
+    locations
+}
+
+#[cfg(test)]
+mod tests;
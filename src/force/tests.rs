@@ -0,0 +1,125 @@
+use super::*;
+
+fn distance(layout: &GraphLayout, first: usize, second: usize) -> f32 {
+    (layout.node_location(first) - layout.node_location(second)).magnitude()
+}
+
+#[test]
+fn test_connected_nodes_attract() {
+    let mut layout = GraphLayout::from_locations(2, vec![-50.0, 0.0, 50.0, 0.0]);
+    layout.add_edges(&[(0, 1, 1.0)]).unwrap();
+    let mut force_layout = ForceLayout::new(1.0, 0.0, 1.0, 10.0, 0.0);
+
+    force_layout.step(&mut layout);
+
+    assert!(distance(&layout, 0, 1) < 100.0);
+}
+
+#[test]
+fn test_unconnected_nodes_repel() {
+    let mut layout = GraphLayout::from_locations(2, vec![-1.0, 0.0, 1.0, 0.0]);
+    let mut force_layout = ForceLayout::new(10.0, 0.0, 1.0, 10.0, 0.0);
+
+    force_layout.step(&mut layout);
+
+    assert!(distance(&layout, 0, 1) > 2.0);
+}
+
+#[test]
+fn test_gravity_pulls_towards_origin() {
+    let mut layout = GraphLayout::from_locations(1, vec![30.0, 40.0]);
+    let mut force_layout = ForceLayout::new(10.0, 1.0, 1.0, 10.0, 0.0);
+
+    force_layout.step(&mut layout);
+
+    assert!(layout.node_location(0).magnitude() < 50.0);
+}
+
+#[test]
+fn test_displacement_is_capped() {
+    let mut layout = GraphLayout::from_locations(2, vec![-500.0, 0.0, 500.0, 0.0]);
+    layout.add_edges(&[(0, 1, 1.0)]).unwrap();
+    let mut force_layout = ForceLayout::new(1.0, 0.0, 1.0, 5.0, 0.0);
+
+    force_layout.step(&mut layout);
+
+    let moved = (layout.node_location(0) - Vector2::new(-500.0, 0.0)).magnitude();
+    assert!(moved <= 5.0 + 1e-4);
+}
+
+#[test]
+fn test_step_moves_nodes_as_one_revision() {
+    let mut layout = GraphLayout::from_locations(3, vec![-1.0, 0.0, 1.0, 0.0, 0.0, 1.0]);
+    let mut force_layout = ForceLayout::new(10.0, 0.0, 1.0, 10.0, 0.0);
+    let revision = layout.locations_revision();
+
+    force_layout.step(&mut layout);
+
+    assert_eq!(layout.locations_revision(), revision + 1);
+}
+
+#[test]
+fn test_layout_settles_connected_pair() {
+    let mut layout = GraphLayout::from_locations(3, vec![-80.0, 0.0, 80.0, 10.0, 0.0, 90.0]);
+    layout.add_edges(&[(0, 1, 1.0), (1, 2, 1.0)]).unwrap();
+    let mut force_layout = ForceLayout::default();
+
+    for _ in 0..500 {
+        force_layout.step(&mut layout);
+    }
+
+    for node in 0..3 {
+        let location = layout.node_location(node);
+        assert!(location.x.is_finite() && location.y.is_finite());
+    }
+    // Repulsion keeps the nodes apart while attraction keeps them together
+    assert!(distance(&layout, 0, 1) > 0.1);
+    assert!(distance(&layout, 0, 1) < 100.0);
+}
+
+#[test]
+fn test_spiral_locations_are_distinct() {
+    let locations = spiral_locations(50, 1.0);
+
+    assert_eq!(locations.len(), 100);
+    for first in 0..50 {
+        for second in (first + 1)..50 {
+            let dx = locations[first * 2] - locations[second * 2];
+            let dy = locations[first * 2 + 1] - locations[second * 2 + 1];
+            assert!((dx * dx + dy * dy).sqrt() > 0.5);
+        }
+    }
+}
+
+#[test]
+fn test_barnes_hut_step_close_to_exact_step() {
+    let locations = spiral_locations(300, 5.0);
+    let mut exact_layout = GraphLayout::from_locations(300, locations.clone());
+    let mut approximate_layout = GraphLayout::from_locations(300, locations);
+    let mut exact = ForceLayout::new(10.0, 1.0, 1.0, 10.0, 0.0);
+    let mut approximate = ForceLayout::new(10.0, 1.0, 1.0, 10.0, 0.5);
+
+    exact.step(&mut exact_layout);
+    approximate.step(&mut approximate_layout);
+
+    for node in 0..300 {
+        let difference =
+            (exact_layout.node_location(node) - approximate_layout.node_location(node)).magnitude();
+        assert!(difference < 0.5, "node {} differs by {}", node, difference);
+    }
+}
+
+#[test]
+fn test_pinned_node_does_not_move() {
+    let mut layout = GraphLayout::from_locations(2, vec![-50.0, 0.0, 50.0, 0.0]);
+    layout.add_edges(&[(0, 1, 1.0)]).unwrap();
+    layout.set_pinned(0, true);
+    let mut force_layout = ForceLayout::default();
+
+    for _ in 0..10 {
+        force_layout.step(&mut layout);
+    }
+
+    assert_eq!(layout.node_location(0), Vector2::new(-50.0, 0.0));
+    assert!(distance(&layout, 0, 1) < 100.0);
+}
@@ -9,7 +9,7 @@ pub struct Vector2 {
 }
 
 impl Vector2 {
-    pub fn new(x: f32, y: f32) -> Vector2 {
+    pub const fn new(x: f32, y: f32) -> Vector2 {
         Vector2 { x, y }
     }
 
@@ -134,13 +134,68 @@ pub fn layout_to_clipspace(
     Vector2::new(x, y)
 }
 
+/// Translates a point in display space back to graph layout space
+///
+/// (The inverse of `layout_to_clipspace`.)
+pub fn clipspace_to_layout(
+    clipspace_location: Vector2,
+    display_offset: Vector2,
+    display_scale: f32,
+    aspect_ratio: f32,
+) -> Vector2 {
+    let x = (clipspace_location.x * aspect_ratio) / display_scale + display_offset.x;
+    let y = clipspace_location.y / display_scale + display_offset.y;
+    Vector2::new(x, y)
+}
+
+/// Translates a point in display space to page pixels, which have their
+/// origin at the top left with y increasing downwards
+pub fn clipspace_to_page(
+    clipspace_location: Vector2,
+    display_width: f32,
+    display_height: f32,
+) -> Vector2 {
+    let x = ((clipspace_location.x + 1.0) / 2.0) * display_width;
+    let y = display_height - (((clipspace_location.y + 1.0) / 2.0) * display_height);
+    Vector2::new(x, y)
+}
+
+/// Translates page pixels to a point in display space
+///
+/// (The inverse of `clipspace_to_page`.)
+pub fn page_to_clipspace(
+    page_location: Vector2,
+    display_width: f32,
+    display_height: f32,
+) -> Vector2 {
+    let x = (page_location.x / display_width) * 2.0 - 1.0;
+    let y = 1.0 - (page_location.y / display_height) * 2.0;
+    Vector2::new(x, y)
+}
+
+/// Converts a distance in page pixels to graph layout space
+///
+/// Clipspace is stretched to the aspect ratio, so the result is the same
+/// horizontally and vertically.
+pub fn page_distance_to_layout(page_distance: f32, display_height: f32, display_scale: f32) -> f32 {
+    page_distance * 2.0 / (display_height * display_scale)
+}
+
+/// Returns the display offset that keeps `anchor`, a point in graph layout
+/// space, at the same place on screen when the display scale is multiplied
+/// by `factor`
+pub fn zoom_offset(display_offset: Vector2, anchor: Vector2, factor: f32) -> Vector2 {
+    anchor + (display_offset - anchor) / factor
+}
+
+#[derive(Debug, Clone, Copy)]
 pub struct Rect {
     pub bottom_left: Vector2,
     pub top_right: Vector2,
 }
 
 impl Rect {
-    pub fn new(bottom_left: Vector2, top_right: Vector2) -> Rect {
+    pub const fn new(bottom_left: Vector2, top_right: Vector2) -> Rect {
         Rect {
             bottom_left,
             top_right,
@@ -153,6 +208,22 @@ impl Rect {
             && point.y > self.bottom_left.y
             && point.y < self.top_right.y
     }
+
+    /// Like `contains`, but also true for points on the edges
+    pub fn encloses(&self, point: Vector2) -> bool {
+        point.x >= self.bottom_left.x
+            && point.x <= self.top_right.x
+            && point.y >= self.bottom_left.y
+            && point.y <= self.top_right.y
+    }
+
+    /// Whether the rects share any point, edges included
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.bottom_left.x <= other.top_right.x
+            && other.bottom_left.x <= self.top_right.x
+            && self.bottom_left.y <= other.top_right.y
+            && other.bottom_left.y <= self.top_right.y
+    }
 }
 
 pub struct Points {
@@ -161,7 +232,7 @@ pub struct Points {
 
 impl Points {
     pub fn new(data: Vec<f32>) -> Points {
-        return Points { data };
+        Points { data }
     }
 
     pub fn len(&self) -> usize {
@@ -176,7 +247,13 @@ impl Points {
         }
     }
 
-    pub fn iter(&self) -> PointsIter {
+    pub fn set_point(&mut self, index: usize, point: Vector2) {
+        let start_index = index * 2;
+        self.data[start_index] = point.x;
+        self.data[start_index + 1] = point.y;
+    }
+
+    pub fn iter(&self) -> PointsIter<'_> {
         PointsIter {
             points: self,
             index: 0,
@@ -193,11 +270,12 @@ impl Points {
         display_scale: &f32,
         aspect_ratio: &f32,
     ) -> Points {
-        let mut clipspace_data: Vec<f32> = vec![0.0; self.data.len()];
-        for index in (0..self.data.len()).step_by(2) {
-            clipspace_data[index] =
-                ((self.data[index] - display_offset.x) * display_scale) / aspect_ratio;
-            clipspace_data[index + 1] = (self.data[index + 1] - display_offset.y) * display_scale;
+        let mut clipspace_data: Vec<f32> = Vec::with_capacity(self.data.len());
+        for point in self.iter() {
+            let clipspace_point =
+                layout_to_clipspace(point, display_offset, *display_scale, *aspect_ratio);
+            clipspace_data.push(clipspace_point.x);
+            clipspace_data.push(clipspace_point.y);
         }
         Points {
             data: clipspace_data,
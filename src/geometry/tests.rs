@@ -39,6 +39,65 @@ fn test_layout_to_clipspace_square_with_offset_and_aspect_ratio() {
     assert_eq!(resulting_location, Vector2::new(-0.3125, -0.5))
 }
 
+#[test]
+fn test_clipspace_to_layout_inverts_layout_to_clipspace() {
+    let layout_location = Vector2::new(3.0, 4.0);
+    let display_offset = Vector2::new(3.5, 4.5);
+    let display_scale = 0.5;
+    let aspect_ratio = 1.6;
+
+    let clipspace_location =
+        layout_to_clipspace(layout_location, display_offset, display_scale, aspect_ratio);
+    let resulting_location = clipspace_to_layout(
+        clipspace_location,
+        display_offset,
+        display_scale,
+        aspect_ratio,
+    );
+
+    assert_eq!(resulting_location, layout_location)
+}
+
+#[test]
+fn test_clipspace_to_page_corners() {
+    let display_width = 800.0;
+    let display_height = 600.0;
+
+    let top_left = clipspace_to_page(Vector2::new(-1.0, 1.0), display_width, display_height);
+    let bottom_right = clipspace_to_page(Vector2::new(1.0, -1.0), display_width, display_height);
+
+    assert_eq!(top_left, Vector2::new(0.0, 0.0));
+    assert_eq!(bottom_right, Vector2::new(800.0, 600.0));
+}
+
+#[test]
+fn test_page_to_clipspace_inverts_clipspace_to_page() {
+    let page_location = Vector2::new(200.0, 450.0);
+
+    let clipspace_location = page_to_clipspace(page_location, 800.0, 600.0);
+    let resulting_location = clipspace_to_page(clipspace_location, 800.0, 600.0);
+
+    assert_eq!(clipspace_location, Vector2::new(-0.5, -0.5));
+    assert_eq!(resulting_location, page_location)
+}
+
+#[test]
+fn test_page_distance_to_layout() {
+    let (width, height, scale, aspect_ratio) = (800.0, 600.0, 0.5, 800.0 / 600.0);
+    let offset = Vector2::new(10.0, -5.0);
+    let to_layout = |page_location| {
+        let clipspace_location = page_to_clipspace(page_location, width, height);
+        clipspace_to_layout(clipspace_location, offset, scale, aspect_ratio)
+    };
+
+    let distance = page_distance_to_layout(30.0, height, scale);
+    let horizontal = to_layout(Vector2::new(130.0, 50.0)) - to_layout(Vector2::new(100.0, 50.0));
+    let vertical = to_layout(Vector2::new(100.0, 80.0)) - to_layout(Vector2::new(100.0, 50.0));
+
+    assert!((horizontal.magnitude() - distance).abs() < 1e-4);
+    assert!((vertical.magnitude() - distance).abs() < 1e-4);
+}
+
 #[test]
 fn test_rect_contains_point_inside() {
     let bottom_left = Vector2::new(0.0, 0.0);
@@ -137,3 +196,40 @@ fn test_rect_contains_point_left() {
 
     assert!(!rect.contains(point_left));
 }
+
+#[test]
+fn test_rect_encloses_point_on_edge() {
+    let rect = Rect::new(Vector2::new(0.0, 0.0), Vector2::new(5.5, 4.5));
+
+    let point_on_edge = Vector2::new(5.5, 2.0);
+
+    assert!(rect.encloses(point_on_edge));
+    assert!(!rect.contains(point_on_edge));
+}
+
+#[test]
+fn test_rect_intersects() {
+    let rect = Rect::new(Vector2::new(0.0, 0.0), Vector2::new(5.5, 4.5));
+    let overlapping = Rect::new(Vector2::new(5.0, -1.0), Vector2::new(8.0, 1.0));
+    let touching = Rect::new(Vector2::new(5.5, 4.5), Vector2::new(6.0, 6.0));
+    let apart = Rect::new(Vector2::new(6.0, 0.0), Vector2::new(7.0, 1.0));
+
+    assert!(rect.intersects(&overlapping));
+    assert!(rect.intersects(&touching));
+    assert!(!rect.intersects(&apart));
+}
+
+#[test]
+fn test_zoom_offset_keeps_anchor_on_screen() {
+    let display_offset = Vector2::new(1.0, -2.0);
+    let anchor = Vector2::new(5.0, 4.0);
+    let display_scale = 0.5;
+    let aspect_ratio = 1.5;
+    let before = layout_to_clipspace(anchor, display_offset, display_scale, aspect_ratio);
+
+    let zoomed_offset = zoom_offset(display_offset, anchor, 2.0);
+
+    let after = layout_to_clipspace(anchor, zoomed_offset, display_scale * 2.0, aspect_ratio);
+    assert_eq!(after, before);
+    assert_eq!(zoomed_offset, Vector2::new(3.0, 1.0));
+}
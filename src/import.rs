@@ -0,0 +1,543 @@
+//! Builds a graph from GraphML and GEXF files, as exported by NetworkX and
+//! Gephi, or from CSV and TSV edge and node lists
+//!
+//! Each node's id in the file becomes its username in the node store, unless
+//! it has a username attribute, and its label becomes its name. Nodes are
+//! ranked by their rank attribute, or else in the order they are first
+//! mentioned. Undirected edges are added in both directions, and nodes
+//! without a position are placed on a spiral.
+
+use crate::{adjacency, force, nodes, GraphLayout};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use wasm_bindgen::JsValue;
+
+const SPIRAL_SPACING: f32 = 10.0;
+// Edge list headers recognised for each column, ignoring case
+const SOURCE_HEADERS: [&str; 3] = ["source", "from", "src"];
+const TARGET_HEADERS: [&str; 3] = ["target", "to", "dst"];
+const WEIGHT_HEADERS: [&str; 2] = ["weight", "value"];
+
+#[derive(Debug)]
+pub enum ImportError {
+    Xml(roxmltree::Error),
+    Csv(csv::Error),
+    MissingElement(&'static str),
+    MissingColumn(&'static str),
+    MissingAttribute {
+        element: &'static str,
+        attribute: &'static str,
+    },
+    InvalidNumber(String),
+    InvalidWeight(f32),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportError::Xml(error) => write!(f, "invalid XML: {}", error),
+            ImportError::Csv(error) => write!(f, "invalid CSV: {}", error),
+            ImportError::MissingElement(element) => write!(f, "no <{}> element", element),
+            ImportError::MissingColumn(column) => write!(f, "no {} column", column),
+            ImportError::MissingAttribute { element, attribute } => {
+                write!(f, "<{}> has no {} attribute", element, attribute)
+            }
+            ImportError::InvalidNumber(value) => write!(f, "{:?} is not a number", value),
+            ImportError::InvalidWeight(weight) => write!(
+                f,
+                "edge weight {} is not a finite, non-negative number",
+                weight
+            ),
+        }
+    }
+}
+
+impl From<roxmltree::Error> for ImportError {
+    fn from(error: roxmltree::Error) -> ImportError {
+        ImportError::Xml(error)
+    }
+}
+
+impl From<csv::Error> for ImportError {
+    fn from(error: csv::Error) -> ImportError {
+        ImportError::Csv(error)
+    }
+}
+
+impl From<ImportError> for JsValue {
+    fn from(error: ImportError) -> JsValue {
+        js_sys::Error::new(&format!("Failed to import graph: {}", error)).into()
+    }
+}
+
+pub struct ImportedGraph {
+    pub layout: GraphLayout,
+    pub nodes: nodes::NodeStore,
+}
+
+struct PendingNode {
+    key: String,
+    label: String,
+    position: Option<(f32, f32)>,
+    attributes: BTreeMap<String, Value>,
+}
+
+/// Collects nodes by their id in the file, giving each a dense index
+#[derive(Default)]
+struct GraphBuilder {
+    indices: HashMap<String, usize>,
+    nodes: Vec<PendingNode>,
+    edges: Vec<(u32, u32, f32)>,
+}
+
+impl GraphBuilder {
+    fn node(&mut self, key: &str) -> &mut PendingNode {
+        let index = self.node_index(key);
+        &mut self.nodes[index]
+    }
+
+    fn node_index(&mut self, key: &str) -> usize {
+        if let Some(&index) = self.indices.get(key) {
+            return index;
+        }
+        let index = self.nodes.len();
+        self.indices.insert(key.to_string(), index);
+        self.nodes.push(PendingNode {
+            key: key.to_string(),
+            label: String::new(),
+            position: None,
+            attributes: BTreeMap::new(),
+        });
+        index
+    }
+
+    fn add_edge(
+        &mut self,
+        source: &str,
+        target: &str,
+        weight: f32,
+        directed: bool,
+    ) -> Result<(), ImportError> {
+        if !weight.is_finite() || weight < 0.0 {
+            return Err(ImportError::InvalidWeight(weight));
+        }
+        let source = u32::try_from(self.node_index(source)).expect("Node index should fit u32");
+        let target = u32::try_from(self.node_index(target)).expect("Node index should fit u32");
+        self.edges.push((source, target, weight));
+        if !directed && source != target {
+            self.edges.push((target, source, weight));
+        }
+        Ok(())
+    }
+
+    fn build(mut self) -> ImportedGraph {
+        let mut locations = force::spiral_locations(self.nodes.len(), SPIRAL_SPACING);
+        for (index, node) in self.nodes.iter().enumerate() {
+            if let Some((x, y)) = node.position {
+                locations[index * 2] = x;
+                locations[index * 2 + 1] = y;
+            }
+        }
+        let node_targets = adjacency::Adjacency::from_edges(self.nodes.len(), &mut self.edges)
+            .expect("Imported edges should join imported nodes");
+        let layout = GraphLayout::from_adjacency(node_targets, locations.clone());
+        let nodes = self
+            .nodes
+            .into_iter()
+            .enumerate()
+            .map(|(index, mut node)| {
+                let username = take_attribute(&mut node.attributes, "username", |value| {
+                    value
+                        .as_str()
+                        .filter(|username| !username.is_empty())
+                        .map(str::to_string)
+                });
+                let rank = take_attribute(&mut node.attributes, "rank", |value| match value {
+                    Value::String(text) => text.trim().parse().ok(),
+                    value => value.as_u64().and_then(|rank| u32::try_from(rank).ok()),
+                });
+                nodes::Node {
+                    id: index,
+                    username: username.unwrap_or(node.key),
+                    name: node.label,
+                    rank: rank.unwrap_or(index as u32 + 1),
+                    x: locations[index * 2],
+                    y: locations[index * 2 + 1],
+                    attributes: node.attributes,
+                }
+            })
+            .collect();
+        ImportedGraph {
+            layout,
+            nodes: nodes::NodeStore::new(nodes),
+        }
+    }
+}
+
+/// Removes and returns an attribute when `parse` accepts its value
+fn take_attribute<T>(
+    attributes: &mut BTreeMap<String, Value>,
+    name: &str,
+    parse: impl Fn(&Value) -> Option<T>,
+) -> Option<T> {
+    let parsed = attributes.get(name).and_then(parse)?;
+    attributes.remove(name);
+    Some(parsed)
+}
+
+/// Declared data key or attribute, with its value type and default
+#[derive(Clone)]
+struct AttributeKey {
+    name: String,
+    kind: String,
+    default: Option<String>,
+}
+
+fn parse_number(value: &str) -> Result<f32, ImportError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| ImportError::InvalidNumber(value.to_string()))
+}
+
+/// Converts an attribute's text to JSON according to its declared type
+fn typed_value(value: &str, kind: &str) -> Result<Value, ImportError> {
+    let invalid = || ImportError::InvalidNumber(value.to_string());
+    match kind {
+        "int" | "integer" | "long" => {
+            let number: i64 = value.trim().parse().map_err(|_| invalid())?;
+            Ok(Value::from(number))
+        }
+        "float" | "double" => {
+            let number: f64 = value.trim().parse().map_err(|_| invalid())?;
+            serde_json::Number::from_f64(number)
+                .map(Value::Number)
+                .ok_or_else(invalid)
+        }
+        "boolean" => Ok(Value::Bool(matches!(value.trim(), "true" | "1"))),
+        _ => Ok(Value::String(value.to_string())),
+    }
+}
+
+fn required_attribute<'a>(
+    node: roxmltree::Node<'a, '_>,
+    element: &'static str,
+    attribute: &'static str,
+) -> Result<&'a str, ImportError> {
+    node.attribute(attribute)
+        .ok_or(ImportError::MissingAttribute { element, attribute })
+}
+
+fn child_element<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    name: &str,
+) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+fn child_elements<'a, 'input: 'a>(
+    node: roxmltree::Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = roxmltree::Node<'a, 'input>> + 'a {
+    node.children()
+        .filter(move |child| child.has_tag_name(name))
+}
+
+/// Reads GraphML, taking positions from data keys named x and y, labels from
+/// a key named label and edge weights from a key named weight
+pub fn from_graphml(text: &str) -> Result<ImportedGraph, ImportError> {
+    let document = roxmltree::Document::parse(text)?;
+    let root = document.root_element();
+    let graph = child_element(root, "graph").ok_or(ImportError::MissingElement("graph"))?;
+
+    let mut node_keys = HashMap::new();
+    let mut edge_keys = HashMap::new();
+    for key in child_elements(root, "key") {
+        let id = required_attribute(key, "key", "id")?;
+        let attribute_key = AttributeKey {
+            name: key.attribute("attr.name").unwrap_or(id).to_string(),
+            kind: key.attribute("attr.type").unwrap_or("string").to_string(),
+            default: child_element(key, "default")
+                .and_then(|default| default.text())
+                .map(str::to_string),
+        };
+        match key.attribute("for").unwrap_or("all") {
+            "node" => {
+                node_keys.insert(id, attribute_key);
+            }
+            "edge" => {
+                edge_keys.insert(id, attribute_key);
+            }
+            "all" => {
+                node_keys.insert(id, attribute_key.clone());
+                edge_keys.insert(id, attribute_key);
+            }
+            _ => {}
+        }
+    }
+    let directed_by_default = graph.attribute("edgedefault") != Some("undirected");
+    let weight_key = edge_keys
+        .iter()
+        .find(|(_, attribute_key)| attribute_key.name == "weight");
+
+    let mut builder = GraphBuilder::default();
+    for element in child_elements(graph, "node") {
+        let id = required_attribute(element, "node", "id")?;
+        let mut data = HashMap::new();
+        for datum in child_elements(element, "data") {
+            let key = required_attribute(datum, "data", "key")?;
+            if let Some(attribute_key) = node_keys.get(key) {
+                data.insert(attribute_key.name.as_str(), (attribute_key, datum.text()));
+            }
+        }
+        for attribute_key in node_keys.values() {
+            if let Some(default) = &attribute_key.default {
+                data.entry(attribute_key.name.as_str())
+                    .or_insert((attribute_key, Some(default)));
+            }
+        }
+
+        let node = builder.node(id);
+        let x = data.remove("x");
+        let y = data.remove("y");
+        if let (Some((_, Some(x))), Some((_, Some(y)))) = (x, y) {
+            node.position = Some((parse_number(x)?, parse_number(y)?));
+        }
+        if let Some((_, Some(label))) = data.remove("label") {
+            node.label = label.to_string();
+        }
+        for (name, (attribute_key, value)) in data {
+            let value = typed_value(value.unwrap_or(""), &attribute_key.kind)?;
+            node.attributes.insert(name.to_string(), value);
+        }
+    }
+    for element in child_elements(graph, "edge") {
+        let source = required_attribute(element, "edge", "source")?;
+        let target = required_attribute(element, "edge", "target")?;
+        let directed = match element.attribute("directed") {
+            Some(directed) => directed == "true",
+            None => directed_by_default,
+        };
+        let mut weight = 1.0;
+        if let Some((&key_id, attribute_key)) = weight_key {
+            let datum = child_elements(element, "data")
+                .find(|datum| datum.attribute("key") == Some(key_id))
+                .and_then(|datum| datum.text());
+            if let Some(value) = datum.or(attribute_key.default.as_deref()) {
+                weight = parse_number(value)?;
+            }
+        }
+        builder.add_edge(source, target, weight, directed)?;
+    }
+    Ok(builder.build())
+}
+
+/// Mutual edges go both ways, like undirected ones
+fn is_directed_gexf_type(edge_type: Option<&str>) -> bool {
+    !matches!(edge_type, Some("undirected") | Some("mutual"))
+}
+
+/// Reads GEXF, taking positions from `viz:position` elements
+pub fn from_gexf(text: &str) -> Result<ImportedGraph, ImportError> {
+    let document = roxmltree::Document::parse(text)?;
+    let graph = child_element(document.root_element(), "graph")
+        .ok_or(ImportError::MissingElement("graph"))?;
+
+    let mut node_attributes = HashMap::new();
+    for attributes in child_elements(graph, "attributes") {
+        if attributes.attribute("class") != Some("node") {
+            continue;
+        }
+        for attribute in child_elements(attributes, "attribute") {
+            let id = required_attribute(attribute, "attribute", "id")?;
+            node_attributes.insert(
+                id,
+                AttributeKey {
+                    name: attribute.attribute("title").unwrap_or(id).to_string(),
+                    kind: attribute.attribute("type").unwrap_or("string").to_string(),
+                    default: child_element(attribute, "default")
+                        .and_then(|default| default.text())
+                        .map(str::to_string),
+                },
+            );
+        }
+    }
+    let directed_by_default = is_directed_gexf_type(graph.attribute("defaultedgetype"));
+
+    let mut builder = GraphBuilder::default();
+    let node_elements = child_element(graph, "nodes")
+        .into_iter()
+        .flat_map(|nodes| child_elements(nodes, "node"));
+    for element in node_elements {
+        let id = required_attribute(element, "node", "id")?;
+        let node = builder.node(id);
+        if let Some(label) = element.attribute("label") {
+            node.label = label.to_string();
+        }
+        if let Some(position) = child_element(element, "position") {
+            let x = required_attribute(position, "position", "x")?;
+            let y = required_attribute(position, "position", "y")?;
+            node.position = Some((parse_number(x)?, parse_number(y)?));
+        }
+        let values = child_element(element, "attvalues")
+            .into_iter()
+            .flat_map(|values| child_elements(values, "attvalue"));
+        for value in values {
+            // GEXF 1.1 names the attribute with `id` rather than `for`
+            let id = value
+                .attribute("for")
+                .or_else(|| value.attribute("id"))
+                .ok_or(ImportError::MissingAttribute {
+                    element: "attvalue",
+                    attribute: "for",
+                })?;
+            let text = required_attribute(value, "attvalue", "value")?;
+            if let Some(attribute) = node_attributes.get(id) {
+                let value = typed_value(text, &attribute.kind)?;
+                node.attributes.insert(attribute.name.clone(), value);
+            }
+        }
+        for attribute in node_attributes.values() {
+            if let Some(default) = &attribute.default {
+                if !node.attributes.contains_key(&attribute.name) {
+                    let value = typed_value(default, &attribute.kind)?;
+                    node.attributes.insert(attribute.name.clone(), value);
+                }
+            }
+        }
+    }
+    let edge_elements = child_element(graph, "edges")
+        .into_iter()
+        .flat_map(|edges| child_elements(edges, "edge"));
+    for element in edge_elements {
+        let source = required_attribute(element, "edge", "source")?;
+        let target = required_attribute(element, "edge", "target")?;
+        let directed = match element.attribute("type") {
+            Some(edge_type) => is_directed_gexf_type(Some(edge_type)),
+            None => directed_by_default,
+        };
+        let weight = match element.attribute("weight") {
+            Some(weight) => parse_number(weight)?,
+            None => 1.0,
+        };
+        builder.add_edge(source, target, weight, directed)?;
+    }
+    Ok(builder.build())
+}
+
+/// Tab-separated if the first line has a tab, otherwise comma-separated
+fn csv_reader(text: &str) -> csv::Reader<&[u8]> {
+    let first_line = text.lines().next().unwrap_or("");
+    let delimiter = if first_line.contains('\t') {
+        b'\t'
+    } else {
+        b','
+    };
+    csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(text.as_bytes())
+}
+
+fn column(headers: &csv::StringRecord, name: &str) -> Option<usize> {
+    column_of_any(headers, &[name])
+}
+
+/// Position of the first header matching any of `names`, ignoring case
+fn column_of_any(headers: &csv::StringRecord, names: &[&str]) -> Option<usize> {
+    headers
+        .iter()
+        .position(|header| names.iter().any(|name| header.eq_ignore_ascii_case(name)))
+}
+
+/// Reads an edge list of source, target and optional weight columns, and
+/// optionally a node list with id, label, x and y columns
+///
+/// The edge list may start with a header naming its source, target and
+/// weight columns, as Gephi writes, otherwise the columns are taken in that
+/// order. Headers may also use from, to and value, or src and dst, in any
+/// case. The node list must have a header, and its other columns become
+/// string attributes. Empty weights count as one and empty coordinates as
+/// missing.
+pub fn from_csv(edges: &str, nodes: Option<&str>) -> Result<ImportedGraph, ImportError> {
+    let mut builder = GraphBuilder::default();
+    if let Some(nodes) = nodes {
+        read_node_csv(&mut builder, nodes)?;
+    }
+
+    let mut records = csv_reader(edges).into_records().peekable();
+    let mut columns = (0, 1, Some(2));
+    if let Some(Ok(first)) = records.peek() {
+        let source = column_of_any(first, &SOURCE_HEADERS);
+        let target = column_of_any(first, &TARGET_HEADERS);
+        if let (Some(source), Some(target)) = (source, target) {
+            columns = (source, target, column_of_any(first, &WEIGHT_HEADERS));
+            records.next();
+        }
+    }
+    let (source_column, target_column, weight_column) = columns;
+    for record in records {
+        let record = record?;
+        if record.iter().all(str::is_empty) {
+            continue;
+        }
+        let source = record
+            .get(source_column)
+            .ok_or(ImportError::MissingColumn("source"))?;
+        let target = record
+            .get(target_column)
+            .ok_or(ImportError::MissingColumn("target"))?;
+        let weight = match weight_column.and_then(|weight_column| record.get(weight_column)) {
+            Some(weight) if !weight.is_empty() => parse_number(weight)?,
+            _ => 1.0,
+        };
+        builder.add_edge(source, target, weight, true)?;
+    }
+    Ok(builder.build())
+}
+
+fn read_node_csv(builder: &mut GraphBuilder, text: &str) -> Result<(), ImportError> {
+    let mut reader = csv_reader(text);
+    let mut records = reader.records();
+    let headers = match records.next() {
+        Some(headers) => headers?,
+        None => return Ok(()),
+    };
+    let id_column = column(&headers, "id").ok_or(ImportError::MissingColumn("id"))?;
+    let label_column = column(&headers, "label");
+    let x_column = column(&headers, "x");
+    let y_column = column(&headers, "y");
+    for record in records {
+        let record = record?;
+        let Some(id) = record.get(id_column).filter(|id| !id.is_empty()) else {
+            continue;
+        };
+        let node = builder.node(id);
+        for (index, value) in record.iter().enumerate() {
+            if index == id_column || Some(index) == x_column || Some(index) == y_column {
+                continue;
+            }
+            if Some(index) == label_column {
+                node.label = value.to_string();
+            } else if let Some(header) = headers.get(index) {
+                node.attributes
+                    .insert(header.to_string(), Value::String(value.to_string()));
+            }
+        }
+        let coordinate = |column: Option<usize>| {
+            column
+                .and_then(|column| record.get(column))
+                .filter(|value| !value.is_empty())
+        };
+        if let (Some(x), Some(y)) = (coordinate(x_column), coordinate(y_column)) {
+            node.position = Some((parse_number(x)?, parse_number(y)?));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,260 @@
+use super::*;
+use crate::geometry::Vector2;
+
+const GRAPHML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="d0" for="node" attr.name="label" attr.type="string"/>
+  <key id="d1" for="node" attr.name="x" attr.type="float"/>
+  <key id="d2" for="node" attr.name="y" attr.type="float"/>
+  <key id="d3" for="node" attr.name="followers" attr.type="int">
+    <default>0</default>
+  </key>
+  <key id="d4" for="edge" attr.name="weight" attr.type="double"/>
+  <graph id="G" edgedefault="directed">
+    <node id="alice">
+      <data key="d0">Alice</data>
+      <data key="d1">1.5</data>
+      <data key="d2">-2.0</data>
+      <data key="d3">120</data>
+    </node>
+    <node id="bob"/>
+    <edge source="alice" target="bob">
+      <data key="d4">2.5</data>
+    </edge>
+    <edge source="bob" target="alice"/>
+    <edge source="bob" target="carol" directed="false"/>
+  </graph>
+</graphml>"#;
+
+const GEXF: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gexf xmlns="http://gexf.net/1.3" xmlns:viz="http://gexf.net/1.3/viz" version="1.3">
+  <graph defaultedgetype="directed">
+    <attributes class="node">
+      <attribute id="0" title="verified" type="boolean">
+        <default>false</default>
+      </attribute>
+    </attributes>
+    <nodes>
+      <node id="0" label="Alice">
+        <attvalues>
+          <attvalue for="0" value="true"/>
+        </attvalues>
+        <viz:position x="3.0" y="4.0" z="0.0"/>
+      </node>
+      <node id="1" label="Bob"/>
+    </nodes>
+    <edges>
+      <edge id="0" source="0" target="1" weight="0.5"/>
+      <edge id="1" source="1" target="1" type="undirected"/>
+    </edges>
+  </graph>
+</gexf>"#;
+
+#[test]
+fn test_graphml_nodes_and_attributes() {
+    let imported = from_graphml(GRAPHML).unwrap();
+
+    assert_eq!(imported.nodes.len(), 3);
+    let alice = imported.nodes.get(0).unwrap();
+    assert_eq!(alice.username, "alice");
+    assert_eq!(alice.display_name(), "Alice");
+    assert_eq!(alice.attributes.get("followers"), Some(&Value::from(120)));
+    let bob = imported.nodes.get(1).unwrap();
+    assert_eq!(bob.display_name(), "bob");
+    assert_eq!(bob.attributes.get("followers"), Some(&Value::from(0)));
+}
+
+#[test]
+fn test_graphml_edges() {
+    let imported = from_graphml(GRAPHML).unwrap();
+
+    let layout = imported.layout;
+    assert_eq!(layout.targets(0), &[1]);
+    assert_eq!(layout.target_weights(0), &[2.5]);
+    assert_eq!(layout.targets(1), &[0, 2]);
+    assert_eq!(layout.target_weights(1), &[1.0, 1.0]);
+    assert_eq!(layout.targets(2), &[1]);
+}
+
+#[test]
+fn test_graphml_positions_fall_back_to_spiral() {
+    let imported = from_graphml(GRAPHML).unwrap();
+
+    let spiral = force::spiral_locations(3, SPIRAL_SPACING);
+    assert_eq!(imported.layout.node_location(0), Vector2::new(1.5, -2.0));
+    assert_eq!(
+        imported.layout.node_location(1),
+        Vector2::new(spiral[2], spiral[3])
+    );
+    assert_eq!(imported.nodes.get(1).unwrap().x, spiral[2]);
+}
+
+#[test]
+fn test_graphml_undirected_by_default() {
+    let graphml = r#"<graphml>
+      <graph edgedefault="undirected">
+        <node id="a"/><node id="b"/>
+        <edge source="a" target="b"/>
+      </graph>
+    </graphml>"#;
+
+    let layout = from_graphml(graphml).unwrap().layout;
+
+    assert_eq!(layout.targets(0), &[1]);
+    assert_eq!(layout.targets(1), &[0]);
+}
+
+#[test]
+fn test_graphml_without_graph() {
+    let result = from_graphml("<graphml></graphml>");
+
+    assert!(matches!(result, Err(ImportError::MissingElement("graph"))));
+}
+
+#[test]
+fn test_empty_graphml() {
+    let imported = from_graphml("<graphml><graph/></graphml>").unwrap();
+
+    assert_eq!(imported.layout.node_count(), 0);
+    assert_eq!(imported.nodes.len(), 0);
+}
+
+#[test]
+fn test_graphml_invalid_position() {
+    let graphml = GRAPHML.replace("1.5", "east");
+
+    let result = from_graphml(&graphml);
+
+    assert!(matches!(result, Err(ImportError::InvalidNumber(value)) if value == "east"));
+}
+
+#[test]
+fn test_gexf_nodes_and_attributes() {
+    let imported = from_gexf(GEXF).unwrap();
+
+    assert_eq!(imported.nodes.len(), 2);
+    let alice = imported.nodes.get(0).unwrap();
+    assert_eq!(alice.display_name(), "Alice");
+    assert_eq!(alice.attributes.get("verified"), Some(&Value::Bool(true)));
+    let bob = imported.nodes.get(1).unwrap();
+    assert_eq!(bob.attributes.get("verified"), Some(&Value::Bool(false)));
+    assert_eq!(imported.layout.node_location(0), Vector2::new(3.0, 4.0));
+}
+
+#[test]
+fn test_gexf_edges() {
+    let layout = from_gexf(GEXF).unwrap().layout;
+
+    assert_eq!(layout.targets(0), &[1]);
+    assert_eq!(layout.target_weights(0), &[0.5]);
+    // An undirected self-loop is only added once
+    assert_eq!(layout.targets(1), &[1]);
+}
+
+#[test]
+fn test_gexf_negative_weight() {
+    let gexf = GEXF.replace("weight=\"0.5\"", "weight=\"-1\"");
+
+    let result = from_gexf(&gexf);
+
+    assert!(matches!(result, Err(ImportError::InvalidWeight(weight)) if weight == -1.0));
+}
+
+#[test]
+fn test_invalid_xml() {
+    let result = from_gexf("<gexf><graph></gexf>");
+
+    assert!(matches!(result, Err(ImportError::Xml(_))));
+}
+
+#[test]
+fn test_csv_edges_with_header() {
+    let edges = "Source,Target,Type,Weight\nalice,bob,Directed,2\nbob,carol,Directed,\n";
+
+    let imported = from_csv(edges, None).unwrap();
+
+    let layout = imported.layout;
+    assert_eq!(layout.node_count(), 3);
+    assert_eq!(layout.targets(0), &[1]);
+    assert_eq!(layout.target_weights(0), &[2.0]);
+    assert_eq!(layout.target_weights(1), &[1.0]);
+    assert_eq!(imported.nodes.get(2).unwrap().username, "carol");
+}
+
+#[test]
+fn test_csv_edges_with_alias_header() {
+    let edges = "From,To,Value\nalice,bob,3\n";
+
+    let layout = from_csv(edges, None).unwrap().layout;
+
+    assert_eq!(layout.node_count(), 2);
+    assert_eq!(layout.targets(0), &[1]);
+    assert_eq!(layout.target_weights(0), &[3.0]);
+}
+
+#[test]
+fn test_header_only_csv_is_empty() {
+    for edges in ["", "source,target,weight\n"] {
+        let layout = from_csv(edges, None).unwrap().layout;
+
+        assert_eq!(layout.node_count(), 0);
+        assert_eq!(layout.edge_count(), 0);
+    }
+}
+
+#[test]
+fn test_tsv_edges_without_header() {
+    let edges = "10\t20\t0.5\n20\t10\n";
+
+    let layout = from_csv(edges, None).unwrap().layout;
+
+    assert_eq!(layout.targets(0), &[1]);
+    assert_eq!(layout.target_weights(0), &[0.5]);
+    assert_eq!(layout.targets(1), &[0]);
+    assert_eq!(layout.target_weights(1), &[1.0]);
+}
+
+#[test]
+fn test_csv_nodes() {
+    let nodes = "id,label,x,y,country\nbob,Bob,1.0,2.0,NZ\nalice,\"Smith, Alice\",,,UK\n";
+    let edges = "source,target\nalice,bob\nalice,dave\n";
+
+    let imported = from_csv(edges, Some(nodes)).unwrap();
+
+    assert_eq!(imported.nodes.len(), 3);
+    let bob = imported.nodes.get(0).unwrap();
+    assert_eq!(bob.display_name(), "Bob");
+    assert_eq!(bob.attributes.get("country"), Some(&Value::from("NZ")));
+    assert_eq!(
+        imported.nodes.get(1).unwrap().display_name(),
+        "Smith, Alice"
+    );
+    assert_eq!(imported.layout.targets(1), &[0, 2]);
+    let spiral = force::spiral_locations(3, SPIRAL_SPACING);
+    assert_eq!(imported.layout.node_location(0), Vector2::new(1.0, 2.0));
+    assert_eq!(
+        imported.layout.node_location(1),
+        Vector2::new(spiral[2], spiral[3])
+    );
+}
+
+#[test]
+fn test_csv_nodes_without_id_column() {
+    let result = from_csv("a,b\n", Some("name,x,y\na,0,0\n"));
+
+    assert!(matches!(result, Err(ImportError::MissingColumn("id"))));
+}
+
+#[test]
+fn test_csv_missing_target() {
+    let result = from_csv("a,b\nc\n", None);
+
+    assert!(matches!(result, Err(ImportError::MissingColumn("target"))));
+}
+
+#[test]
+fn test_csv_invalid_weight() {
+    let result = from_csv("a,b,heavy\n", None);
+
+    assert!(matches!(result, Err(ImportError::InvalidNumber(value)) if value == "heavy"));
+}
@@ -1,6 +1,5 @@
 use super::geometry;
 use itertools::Itertools;
-use js_sys;
 use std::collections::{HashMap, HashSet};
 use wasm_bindgen::prelude::*;
 
@@ -32,7 +31,11 @@ fn id_intersection(first: &TouchSet, second: &TouchSet) -> HashSet<i32> {
     first_keys.intersection(&second_keys).copied().collect()
 }
 
-pub fn pinch_diff(sequence: &[TouchSet]) -> f32 {
+/// Ratio of the distance between the fingers common to both touch sets
+/// after the move to the distance before it
+///
+/// A ratio of one means no pinch, as when fewer than two fingers are down.
+pub fn pinch_ratio(sequence: &[TouchSet]) -> f32 {
     let first_set = &sequence[0];
     let second_set = &sequence[1];
     let common_ids = id_intersection(first_set, second_set);
@@ -58,7 +61,20 @@ pub fn pinch_diff(sequence: &[TouchSet]) -> f32 {
             .expect("Key set should contain key in intersection");
         second_dist += (*second_a - *second_b).magnitude();
     }
-    second_dist - first_dist
+    if first_dist > 0.0 && second_dist > 0.0 {
+        second_dist / first_dist
+    } else {
+        1.0
+    }
+}
+
+/// Mean page location of the touches in the set, if there are any
+pub fn touch_centroid(touches: &TouchSet) -> Option<geometry::Vector2> {
+    if touches.data.is_empty() {
+        return None;
+    }
+    let sum: geometry::Vector2 = touches.data.values().copied().sum();
+    Some(sum / touches.data.len() as f32)
 }
 
 pub fn touch_offset(sequence: &[TouchSet]) -> geometry::Vector2 {
@@ -82,3 +98,6 @@ pub fn touch_offset(sequence: &[TouchSet]) -> geometry::Vector2 {
     offset /= count;
     offset
 }
+
+#[cfg(test)]
+mod tests;
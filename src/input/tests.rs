@@ -0,0 +1,38 @@
+use super::*;
+
+fn touch_set(touches: &[(i32, f32, f32)]) -> TouchSet {
+    let data = touches
+        .iter()
+        .map(|&(id, x, y)| (id, geometry::Vector2::new(x, y)))
+        .collect();
+    TouchSet { data }
+}
+
+#[test]
+fn test_pinch_ratio_spreading_fingers() {
+    let before = touch_set(&[(0, 10.0, 10.0), (1, 20.0, 10.0)]);
+    let after = touch_set(&[(0, 5.0, 10.0), (1, 25.0, 10.0)]);
+
+    let ratio = pinch_ratio(&[before, after]);
+
+    assert_eq!(ratio, 2.0);
+}
+
+#[test]
+fn test_pinch_ratio_single_finger() {
+    let before = touch_set(&[(0, 10.0, 10.0)]);
+    let after = touch_set(&[(0, 30.0, 10.0), (1, 40.0, 10.0)]);
+
+    let ratio = pinch_ratio(&[before, after]);
+
+    assert_eq!(ratio, 1.0);
+}
+
+#[test]
+fn test_touch_centroid() {
+    let touches = touch_set(&[(0, 10.0, 20.0), (1, 30.0, 40.0)]);
+
+    let centroid = touch_centroid(&touches);
+
+    assert_eq!(centroid, Some(geometry::Vector2::new(20.0, 30.0)));
+}
@@ -0,0 +1,40 @@
+use crate::geometry::Vector2;
+
+/// A node's label, anchored by its top left corner in page pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Label {
+    pub node: usize,
+    pub page_location: Vector2,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Label {
+    /// Labels that only touch at their edges do not overlap
+    fn overlaps(&self, other: &Label) -> bool {
+        self.page_location.x < other.page_location.x + other.width
+            && other.page_location.x < self.page_location.x + self.width
+            && self.page_location.y < other.page_location.y + other.height
+            && other.page_location.y < self.page_location.y + self.height
+    }
+}
+
+/// Greedily picks at most `max_labels` labels that do not overlap
+///
+/// Labels are considered in the order given, so earlier labels win when two
+/// would overlap.
+pub fn place_labels(candidates: &[Label], max_labels: usize) -> Vec<Label> {
+    let mut placed: Vec<Label> = Vec::new();
+    for candidate in candidates {
+        if placed.len() >= max_labels {
+            break;
+        }
+        if !placed.iter().any(|label| label.overlaps(candidate)) {
+            placed.push(*candidate);
+        }
+    }
+    placed
+}
+
+#[cfg(test)]
+mod tests;
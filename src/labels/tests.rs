@@ -0,0 +1,43 @@
+use super::*;
+
+fn label(node: usize, x: f32, y: f32) -> Label {
+    Label {
+        node,
+        page_location: Vector2::new(x, y),
+        width: 50.0,
+        height: 10.0,
+    }
+}
+
+fn placed_nodes(placed: &[Label]) -> Vec<usize> {
+    placed.iter().map(|label| label.node).collect()
+}
+
+#[test]
+fn test_earlier_labels_win_overlaps() {
+    let candidates = [label(3, 0.0, 0.0), label(1, 20.0, 5.0), label(2, 0.0, 12.0)];
+
+    let placed = place_labels(&candidates, 10);
+
+    assert_eq!(placed_nodes(&placed), vec![3, 2]);
+}
+
+#[test]
+fn test_touching_labels_do_not_overlap() {
+    let candidates = [label(0, 0.0, 0.0), label(1, 50.0, 0.0), label(2, 0.0, 10.0)];
+
+    let placed = place_labels(&candidates, 10);
+
+    assert_eq!(placed_nodes(&placed), vec![0, 1, 2]);
+}
+
+#[test]
+fn test_label_count_is_capped() {
+    let candidates: Vec<Label> = (0..10)
+        .map(|node| label(node, 0.0, node as f32 * 20.0))
+        .collect();
+
+    let placed = place_labels(&candidates, 4);
+
+    assert_eq!(placed_nodes(&placed), vec![0, 1, 2, 3]);
+}
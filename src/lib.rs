@@ -1,23 +1,52 @@
-use byteorder::{ByteOrder, LittleEndian};
-
-use js_sys;
-use log::{debug, Level};
+use log::Level;
+use serde::Serialize;
+use std::cell::OnceCell;
 use std::collections::HashMap;
+use std::fmt;
 use std::panic;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsValue;
 extern crate console_error_panic_hook;
 
+mod adjacency;
+mod centrality;
+mod export;
+mod force;
 mod geometry;
+mod import;
 mod input;
+mod labels;
+mod loader;
+mod nodes;
+mod quadtree;
+mod random;
+mod stats;
 
 const DISPLAY_PAN_RATE: f32 = 1.0;
 const DISPLAY_ZOOM_RATE: f32 = 1.25;
-const DISPLAY_ZOOM_RATE_PINCH: f32 = 1.125;
-const CLIPSPACE_BOUNDS: geometry::Rect = geometry::Rect {
-    bottom_left: geometry::Vector2 { x: -1.0, y: -1.0 },
-    top_right: geometry::Vector2 { x: 1.0, y: 1.0 },
-};
+const PICK_RADIUS_PX: f32 = 12.0;
+const EDGE_ALPHA_BANDS: usize = 4;
+const CLIPSPACE_BOUNDS: geometry::Rect = geometry::Rect::new(
+    geometry::Vector2::new(-1.0, -1.0),
+    geometry::Vector2::new(1.0, 1.0),
+);
+
+/// Band of an edge's line alpha, with the heaviest edges in the last band
+///
+/// Weights such as message counts are heavy-tailed, so bands are spaced
+/// evenly in log(1 + weight) rather than in weight.
+fn edge_alpha_band(weight: f32, max_weight: f32) -> usize {
+    if max_weight <= 0.0 || weight <= 0.0 {
+        return 0;
+    }
+    let fraction = weight.ln_1p() / max_weight.ln_1p();
+    let band = (fraction * EDGE_ALPHA_BANDS as f32).ceil() as usize;
+    band.clamp(1, EDGE_ALPHA_BANDS) - 1
+}
+
+fn edge_band_alpha(band: usize) -> f32 {
+    (band + 1) as f32 / EDGE_ALPHA_BANDS as f32
+}
 
 #[wasm_bindgen]
 pub fn init_logging() {
@@ -33,6 +62,10 @@ pub fn get_memory() -> JsValue {
 #[wasm_bindgen]
 pub struct GraphFacade {
     graph: GraphDisplay,
+    incremental_pagerank: Option<centrality::IncrementalPageRank>,
+    force_layout: force::ForceLayout,
+    nodes: nodes::NodeStore,
+    export_scores: Vec<(String, Vec<f32>)>,
 }
 
 #[wasm_bindgen]
@@ -45,21 +78,285 @@ impl GraphFacade {
         display_scale: f32,
         autopan_rate_mul: f32,
         focus_node_idx: usize,
-    ) -> GraphFacade {
+    ) -> Result<GraphFacade, JsValue> {
         let layout = GraphLayout::new(node_count, node_locations);
-        let display = GraphDisplay::new(
+        GraphFacade::from_parts(
+            layout,
+            nodes::NodeStore::default(),
+            display_width,
+            display_height,
+            display_scale,
+            autopan_rate_mul,
+            focus_node_idx,
+        )
+    }
+
+    /// Builds the graph from the JSON array of nodes in nodes.json, keeping
+    /// their metadata for search and labels
+    pub fn from_nodes_json(
+        nodes_json: &str,
+        display_width: f32,
+        display_height: f32,
+        display_scale: f32,
+        autopan_rate_mul: f32,
+        focus_node_idx: usize,
+    ) -> Result<GraphFacade, JsValue> {
+        let nodes = nodes::NodeStore::from_json(nodes_json)
+            .map_err(|error| JsValue::from_str(&format!("Failed to parse nodes: {}", error)))?;
+        let layout = GraphLayout::from_locations(nodes.len(), nodes.locations());
+        GraphFacade::from_parts(
             layout,
+            nodes,
+            display_width,
+            display_height,
+            display_scale,
+            autopan_rate_mul,
+            focus_node_idx,
+        )
+    }
+
+    /// Builds the graph, edges included, from GraphML text
+    pub fn from_graphml(
+        graphml: &str,
+        display_width: f32,
+        display_height: f32,
+        display_scale: f32,
+        autopan_rate_mul: f32,
+        focus_node_idx: usize,
+    ) -> Result<GraphFacade, JsValue> {
+        let imported = import::from_graphml(graphml)?;
+        GraphFacade::from_parts(
+            imported.layout,
+            imported.nodes,
+            display_width,
+            display_height,
+            display_scale,
+            autopan_rate_mul,
+            focus_node_idx,
+        )
+    }
+
+    /// Builds the graph, edges included, from GEXF text
+    pub fn from_gexf(
+        gexf: &str,
+        display_width: f32,
+        display_height: f32,
+        display_scale: f32,
+        autopan_rate_mul: f32,
+        focus_node_idx: usize,
+    ) -> Result<GraphFacade, JsValue> {
+        let imported = import::from_gexf(gexf)?;
+        GraphFacade::from_parts(
+            imported.layout,
+            imported.nodes,
+            display_width,
+            display_height,
+            display_scale,
+            autopan_rate_mul,
+            focus_node_idx,
+        )
+    }
+
+    /// Builds the graph from a CSV or TSV edge list and optional node list,
+    /// giving each distinct node id a node index
+    pub fn from_csv(
+        edges_csv: &str,
+        nodes_csv: Option<String>,
+        display_width: f32,
+        display_height: f32,
+        display_scale: f32,
+        autopan_rate_mul: f32,
+        focus_node_idx: usize,
+    ) -> Result<GraphFacade, JsValue> {
+        let imported = import::from_csv(edges_csv, nodes_csv.as_deref())?;
+        GraphFacade::from_parts(
+            imported.layout,
+            imported.nodes,
             display_width,
             display_height,
             display_scale,
             autopan_rate_mul,
             focus_node_idx,
+        )
+    }
+
+    /// Includes the scores as a node attribute called `name` in exports,
+    /// replacing any scores already given that name
+    pub fn set_export_scores(&mut self, name: &str, scores: &centrality::CentralityScores) {
+        let values = scores.values().to_vec();
+        match self
+            .export_scores
+            .iter_mut()
+            .find(|(existing, _)| existing == name)
+        {
+            Some((_, existing_values)) => *existing_values = values,
+            None => self.export_scores.push((name.to_string(), values)),
+        }
+    }
+
+    pub fn clear_export_scores(&mut self) {
+        self.export_scores.clear();
+    }
+
+    /// The graph as GraphML, with current node locations and export scores
+    pub fn export_graphml(&self) -> String {
+        export::to_graphml(&self.graph.layout, &self.nodes, &self.export_scores)
+    }
+
+    /// A CSV row per node, with current node locations and export scores
+    pub fn export_nodes_csv(&self) -> String {
+        export::nodes_to_csv(&self.graph.layout, &self.nodes, &self.export_scores)
+    }
+
+    /// A CSV row per edge, with its weight
+    pub fn export_edges_csv(&self) -> String {
+        export::edges_to_csv(&self.graph.layout)
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.graph.layout.node_count()
+    }
+
+    /// The node's metadata as a plain object, including any extra attributes
+    pub fn get_node(&self, id: usize) -> Result<JsValue, JsValue> {
+        // Flattened attributes make the node a map, which would otherwise
+        // become a JavaScript `Map`
+        let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+        match self.nodes.get(id) {
+            Some(node) => Ok(node.serialize(&serializer)?),
+            None => Ok(JsValue::UNDEFINED),
+        }
+    }
+
+    pub fn get_node_display_name(&self, id: usize) -> Option<String> {
+        self.nodes
+            .get(id)
+            .map(|node| node.display_name().to_string())
+    }
+
+    /// Ids of nodes whose username or name contains `query`, in rank order
+    pub fn search_nodes(&self, query: &str) -> js_sys::Uint32Array {
+        let ids: Vec<u32> = self
+            .nodes
+            .search(query)
+            .into_iter()
+            .map(|id| id as u32)
+            .collect();
+        js_sys::Uint32Array::from(ids.as_slice())
+    }
+
+    /// Ids of nodes whose `attribute` equals `value`
+    pub fn filter_nodes(
+        &self,
+        attribute: &str,
+        value: JsValue,
+    ) -> Result<js_sys::Uint32Array, JsValue> {
+        let value: serde_json::Value = serde_wasm_bindgen::from_value(value)?;
+        let ids: Vec<u32> = self
+            .nodes
+            .filter(attribute, &value)
+            .into_iter()
+            .map(|id| id as u32)
+            .collect();
+        Ok(js_sys::Uint32Array::from(ids.as_slice()))
+    }
+
+    pub fn load_edges(&mut self, chunk_array: js_sys::Uint8Array) -> Result<(), JsValue> {
+        self.graph.layout.load_edges(chunk_array)?;
+        if let Some(incremental_pagerank) = self.incremental_pagerank.as_mut() {
+            incremental_pagerank.refine(&self.graph.layout);
+        }
+        Ok(())
+    }
+
+    /// Checks that the edge data ended where the file says it should
+    pub fn finish_loading(&mut self) -> Result<(), JsValue> {
+        Ok(self.graph.layout.finish_loading()?)
+    }
+
+    pub fn set_edge_encoding(&mut self, encoding: loader::EdgeEncoding) -> Result<(), JsValue> {
+        self.graph.layout.set_edge_encoding(encoding)
+    }
+
+    /// Fraction of the edge data loaded so far, from 0 to 1
+    pub fn loading_progress(&self) -> f32 {
+        self.graph.layout.loading_progress()
+    }
+
+    /// Refines PageRank after each chunk passed to `load_edges`, running at
+    /// most `iterations_per_chunk` iterations warm-started from the last chunk
+    pub fn enable_incremental_pagerank(
+        &mut self,
+        damping: f32,
+        tolerance: f32,
+        iterations_per_chunk: usize,
+    ) {
+        self.incremental_pagerank = Some(centrality::IncrementalPageRank::new(
+            &self.graph.layout,
+            damping,
+            tolerance,
+            iterations_per_chunk,
+        ));
+    }
+
+    pub fn get_incremental_pagerank(&self) -> Option<centrality::CentralityScores> {
+        self.incremental_pagerank
+            .as_ref()
+            .map(|incremental_pagerank| incremental_pagerank.scores().clone())
+    }
+
+    /// Estimates each node's final degree from the edges loaded so far
+    pub fn get_degree_estimate(&self, kind: stats::DegreeKind) -> js_sys::Float32Array {
+        let layout = &self.graph.layout;
+        let estimate = stats::estimated_degrees(
+            layout,
+            kind,
+            layout.complete_source_count(),
+            layout.loading_progress(),
+        );
+        js_sys::Float32Array::from(estimate.as_slice())
+    }
+
+    /// Runs iterations of the force-directed layout over the loaded edges
+    pub fn step_layout(&mut self, iterations: usize) {
+        for _ in 0..iterations {
+            self.force_layout.step(&mut self.graph.layout);
+        }
+    }
+
+    /// Sets the layout forces; `theta` controls the Barnes-Hut approximation
+    /// of repulsion, with zero giving exact but quadratic-time repulsion
+    pub fn configure_layout(
+        &mut self,
+        repulsion: f32,
+        gravity: f32,
+        jitter_tolerance: f32,
+        max_displacement: f32,
+        theta: f32,
+    ) {
+        self.force_layout = force::ForceLayout::new(
+            repulsion,
+            gravity,
+            jitter_tolerance,
+            max_displacement,
+            theta,
         );
-        GraphFacade { graph: display }
     }
 
-    pub fn load_edges(&mut self, chunk_array: js_sys::Uint8Array) {
-        self.graph.layout.load_edges(chunk_array);
+    /// Places nodes on an evenly spaced spiral as a starting point for layout
+    pub fn scatter_layout(&mut self, spacing: f32) {
+        let locations = force::spiral_locations(self.graph.layout.node_count(), spacing);
+        self.graph.layout.set_node_locations(locations);
+        self.force_layout.reset();
+    }
+
+    pub fn degree_distribution(&self, kind: stats::DegreeKind) -> stats::DegreeDistribution {
+        stats::DegreeDistribution::new(&self.graph.layout, kind)
+    }
+
+    /// Sum of edge weights per node
+    pub fn node_strengths(&self, kind: stats::DegreeKind) -> js_sys::Float32Array {
+        js_sys::Float32Array::from(stats::strengths(&self.graph.layout, kind).as_slice())
     }
 
     pub fn update_display_size(&mut self, display_width: f32, display_height: f32) {
@@ -78,7 +375,7 @@ impl GraphFacade {
         self.graph.get_vertices_ptr()
     }
 
-    pub fn get_vertex_indices_ptr(&self) -> *const u16 {
+    pub fn get_vertex_indices_ptr(&self) -> *const u32 {
         self.graph.get_vertex_indices_ptr()
     }
 
@@ -86,6 +383,21 @@ impl GraphFacade {
         self.graph.get_vertex_indices_len()
     }
 
+    /// Offsets into the vertex indices where each band of edge alpha starts,
+    /// followed by the total length
+    pub fn get_edge_band_offsets(&self) -> js_sys::Uint32Array {
+        js_sys::Uint32Array::from(self.graph.get_edge_band_offsets())
+    }
+
+    /// Line alpha for each band, from the faintest to fully opaque
+    ///
+    /// The alphas are fixed; only which band an edge falls in depends on the
+    /// heaviest edge's weight.
+    pub fn get_edge_band_alphas(&self) -> js_sys::Float32Array {
+        let alphas: Vec<f32> = (0..EDGE_ALPHA_BANDS).map(edge_band_alpha).collect();
+        js_sys::Float32Array::from(alphas.as_slice())
+    }
+
     pub fn pan(&mut self, x: f32, y: f32) {
         self.graph.pan(x, y);
     }
@@ -98,6 +410,12 @@ impl GraphFacade {
         self.graph.zoom_out();
     }
 
+    /// Scales the display by `factor`, keeping the layout point under the
+    /// page location where it is on screen
+    pub fn zoom_at(&mut self, page_x: f32, page_y: f32, factor: f32) {
+        self.graph.zoom_at(page_x, page_y, factor);
+    }
+
     pub fn touch_start(&mut self, touch: input::TouchSet) {
         self.graph.touch_start(touch);
     }
@@ -110,71 +428,495 @@ impl GraphFacade {
         self.graph.get_visible_node_page_locations()
     }
 
+    /// Returns the page locations of visible nodes to label, at most
+    /// `max_labels` of them, so that no two labels overlap
+    ///
+    /// Higher ranked nodes are labelled first, then nodes without node data
+    /// by index. `label_widths` holds each node's estimated label width in
+    /// pixels.
+    pub fn get_labelled_node_page_locations(
+        &self,
+        label_widths: js_sys::Float32Array,
+        label_height: f32,
+        max_labels: usize,
+    ) -> Result<JsValue, JsValue> {
+        self.graph.get_labelled_node_page_locations(
+            &label_widths.to_vec(),
+            label_height,
+            max_labels,
+            |node_id| self.nodes.rank_order(node_id),
+        )
+    }
+
     pub fn autopan(&mut self, node_id: usize) {
         self.graph.autopan(node_id);
     }
+
+    /// Returns the node drawn closest to the page location, if any is near
+    pub fn pick_node(&self, page_x: f32, page_y: f32) -> Option<usize> {
+        self.graph.pick_node(page_x, page_y)
+    }
+
+    /// Returns the node drawn closest to the page location, if any is within
+    /// `radius_px` pixels, for hit testing hovers and clicks
+    pub fn node_at(&self, page_x: f32, page_y: f32, radius_px: f32) -> Option<usize> {
+        self.graph.node_at(page_x, page_y, radius_px)
+    }
+
+    /// Moves a node under the pointer, pinning it for the rest of the drag
+    pub fn drag_node(&mut self, node_id: usize, page_x: f32, page_y: f32) {
+        self.graph.drag_node(node_id, page_x, page_y);
+        self.force_layout.reset();
+    }
+
+    /// Ends a drag, leaving the node pinned only if it was before the drag
+    pub fn release_node(&mut self, node_id: usize) {
+        self.graph.release_node(node_id);
+    }
+
+    /// Keeps a node fixed during layout until it is unpinned
+    pub fn set_node_pinned(&mut self, node_id: usize, pinned: bool) {
+        self.graph.layout.set_pinned(node_id, pinned);
+    }
+
+    pub fn compute_pagerank(
+        &self,
+        damping: f32,
+        tolerance: f32,
+        max_iter: usize,
+    ) -> centrality::CentralityScores {
+        centrality::pagerank(&self.graph.layout, damping, tolerance, max_iter)
+    }
+
+    pub fn compute_personalized_pagerank(
+        &self,
+        seeds: js_sys::Uint32Array,
+        damping: f32,
+        tolerance: f32,
+        max_iter: usize,
+    ) -> Result<centrality::CentralityScores, JsValue> {
+        let seeds = self.graph.layout.seed_indices(seeds)?;
+        Ok(centrality::personalized_pagerank(
+            &self.graph.layout,
+            &seeds,
+            damping,
+            tolerance,
+            max_iter,
+        ))
+    }
+
+    /// Returns the `count` nodes most relevant to the seeds by personalized
+    /// PageRank, excluding the seeds themselves
+    pub fn related_nodes(
+        &self,
+        seeds: js_sys::Uint32Array,
+        count: usize,
+        damping: f32,
+        tolerance: f32,
+        max_iter: usize,
+    ) -> Result<js_sys::Uint32Array, JsValue> {
+        let seeds = self.graph.layout.seed_indices(seeds)?;
+        let scores = centrality::personalized_pagerank(
+            &self.graph.layout,
+            &seeds,
+            damping,
+            tolerance,
+            max_iter,
+        );
+        let related = centrality::top_nodes(scores.values(), count, &seeds);
+        Ok(js_sys::Uint32Array::from(related.as_slice()))
+    }
+
+    pub fn compute_betweenness(&self, normalized: bool) -> centrality::CentralityScores {
+        centrality::betweenness(&self.graph.layout, normalized)
+    }
+
+    pub fn compute_betweenness_sampled(
+        &self,
+        samples: usize,
+        seed: u32,
+        normalized: bool,
+    ) -> centrality::CentralityScores {
+        centrality::betweenness_sampled(&self.graph.layout, samples, seed as u64, normalized)
+    }
+
+    pub fn compute_closeness(
+        &self,
+        direction: centrality::Direction,
+    ) -> centrality::CentralityScores {
+        centrality::closeness(&self.graph.layout, direction)
+    }
+
+    pub fn compute_harmonic(
+        &self,
+        direction: centrality::Direction,
+        normalized: bool,
+    ) -> centrality::CentralityScores {
+        centrality::harmonic(&self.graph.layout, direction, normalized)
+    }
+
+    pub fn compute_eigenvector(
+        &self,
+        tolerance: f32,
+        max_iter: usize,
+    ) -> centrality::CentralityScores {
+        centrality::eigenvector(&self.graph.layout, tolerance, max_iter)
+    }
+
+    pub fn compute_katz(
+        &self,
+        alpha: f32,
+        beta: f32,
+        tolerance: f32,
+        max_iter: usize,
+        normalized: bool,
+    ) -> centrality::CentralityScores {
+        centrality::katz(
+            &self.graph.layout,
+            alpha,
+            beta,
+            tolerance,
+            max_iter,
+            normalized,
+        )
+    }
+
+    pub fn compute_hits(&self, tolerance: f32, max_iter: usize) -> centrality::HitsScores {
+        centrality::hits(&self.graph.layout, tolerance, max_iter)
+    }
+}
+
+impl GraphFacade {
+    fn from_parts(
+        layout: GraphLayout,
+        nodes: nodes::NodeStore,
+        display_width: f32,
+        display_height: f32,
+        display_scale: f32,
+        autopan_rate_mul: f32,
+        focus_node_idx: usize,
+    ) -> Result<GraphFacade, JsValue> {
+        let mut layout = layout;
+        // Edges added out of order no longer need a second copy
+        layout.compact_edges();
+        let display = GraphDisplay::new(
+            layout,
+            display_width,
+            display_height,
+            display_scale,
+            autopan_rate_mul,
+            focus_node_idx,
+        )?;
+        Ok(GraphFacade {
+            graph: display,
+            incremental_pagerank: None,
+            force_layout: force::ForceLayout::default(),
+            nodes,
+            export_scores: Vec::new(),
+        })
+    }
 }
 
 pub struct GraphLayout {
-    node_targets: Vec<Vec<usize>>,
-    node_sources: Vec<Vec<usize>>,
+    // Edges appended in source order; any added out of order wait in the
+    // unsorted tail until the targets are next read, when both are merged,
+    // and `compact_edges` drops the tail and keeps only the merged copy
+    node_targets: adjacency::Adjacency,
+    unsorted_edges: Vec<(u32, u32, f32)>,
+    merged_targets: OnceCell<adjacency::Adjacency>,
+    // Built from the targets when first read after edges are added
+    node_sources: OnceCell<adjacency::Adjacency>,
     node_locations: geometry::Points,
-    loading_node_index: usize,
-    edges_loaded: usize,
-    loading_remainder: Option<u8>,
+    // Incremented whenever a node moves, so spatial indexes know to rebuild
+    locations_revision: u64,
+    edge_stream: loader::EdgeStream,
+    pinned: Vec<bool>,
 }
 
 impl GraphLayout {
     pub fn new(node_count: usize, locations: js_sys::Float32Array) -> GraphLayout {
-        let node_targets = (0..node_count).map(|_| Vec::new()).collect();
-        let node_sources = (0..node_count).map(|_| Vec::new()).collect();
-        let node_locations = geometry::Points::new(locations.to_vec());
+        GraphLayout::from_locations(node_count, locations.to_vec())
+    }
+
+    pub fn from_locations(node_count: usize, locations: Vec<f32>) -> GraphLayout {
+        GraphLayout::from_adjacency(adjacency::Adjacency::new(node_count), locations)
+    }
+
+    /// Wraps edges that are already built, such as those of an imported file
+    pub fn from_adjacency(node_targets: adjacency::Adjacency, locations: Vec<f32>) -> GraphLayout {
+        let node_count = node_targets.node_count();
+        let node_locations = geometry::Points::new(locations);
         GraphLayout {
             node_targets,
-            node_sources,
+            unsorted_edges: Vec::new(),
+            merged_targets: OnceCell::new(),
+            node_sources: OnceCell::new(),
             node_locations,
-            loading_node_index: 0,
-            edges_loaded: 0,
-            loading_remainder: None,
+            locations_revision: 0,
+            edge_stream: loader::EdgeStream::new(loader::EdgeEncoding::U16, node_count),
+            pinned: vec![false; node_count],
+        }
+    }
+
+    #[cfg(test)]
+    pub fn from_edges(node_count: usize, edges: &[(usize, usize)]) -> GraphLayout {
+        let mut layout = GraphLayout::from_locations(node_count, vec![0.0; node_count * 2]);
+        let weighted_edges: Vec<(usize, usize, f32)> = edges
+            .iter()
+            .map(|&(source, target)| (source, target, 1.0))
+            .collect();
+        layout.add_edges(&weighted_edges).unwrap();
+        layout
+    }
+
+    #[cfg(test)]
+    pub fn from_weighted_edges(node_count: usize, edges: &[(usize, usize, f32)]) -> GraphLayout {
+        let mut layout = GraphLayout::from_locations(node_count, vec![0.0; node_count * 2]);
+        layout.add_edges(edges).unwrap();
+        layout
+    }
+
+    pub fn load_edges(&mut self, chunk_array: js_sys::Uint8Array) -> Result<(), JsValue> {
+        Ok(self.load_edge_chunk(&chunk_array.to_vec())?)
+    }
+
+    pub fn load_edge_chunk(&mut self, chunk: &[u8]) -> Result<(), loader::LoadError> {
+        let edges = self.edge_stream.decode(chunk)?;
+        self.add_edges(&edges).map_err(|error| loader::LoadError {
+            offset: self.edge_stream.bytes_received(),
+            node_index: self.edge_stream.node_index(),
+            kind: loader::LoadErrorKind::TargetOutOfRange {
+                target: error.node,
+                node_count: error.node_count,
+            },
+        })
+    }
+
+    /// Checks the edge data is complete and compacts the edges loaded
+    pub fn finish_loading(&mut self) -> Result<(), loader::LoadError> {
+        self.compact_edges();
+        self.edge_stream.finish()
+    }
+
+    /// Selects how `load_edges` decodes edge data without a header
+    ///
+    /// Must be called before any edges are loaded.
+    pub fn set_edge_encoding(&mut self, encoding: loader::EdgeEncoding) -> Result<(), JsValue> {
+        if self.edge_stream.bytes_received() > 0 {
+            return Err(JsValue::from_str(
+                "Edge encoding cannot change once edges are loading",
+            ));
         }
+        self.edge_stream = loader::EdgeStream::new(encoding, self.node_count());
+        Ok(())
     }
-    pub fn load_edges(&mut self, chunk_array: js_sys::Uint8Array) {
-        let mut chunk_buffer = chunk_array.to_vec();
 
-        if let Some(leftover) = self.loading_remainder {
-            chunk_buffer.insert(0, leftover);
-            self.loading_remainder = None;
+    /// Adds (source, target, weight) edges, after any already added
+    ///
+    /// Edges in source order, as the loader decodes them, are appended to the
+    /// targets in place. Others are merged in once, when the targets are next
+    /// read, and the sources are rebuilt when next read, so loading in chunks
+    /// costs no more than adding every edge at once. Nothing is added if any
+    /// node is out of range.
+    pub fn add_edges(
+        &mut self,
+        edges: &[(usize, usize, f32)],
+    ) -> Result<(), adjacency::NodeOutOfRange> {
+        let node_count = self.node_count();
+        let node = |index: usize| match u32::try_from(index) {
+            Ok(node) if index < node_count => Ok(node),
+            _ => Err(adjacency::NodeOutOfRange {
+                node: index,
+                node_count,
+            }),
+        };
+        let edges = edges
+            .iter()
+            .map(|&(source, target, weight)| Ok((node(source)?, node(target)?, weight)))
+            .collect::<Result<Vec<_>, _>>()?;
+        if edges.is_empty() {
+            return Ok(());
         }
-        if chunk_buffer.len() % 2 == 1 {
-            self.loading_remainder = chunk_buffer.pop();
+        if let Some(merged_targets) = self.merged_targets.take() {
+            self.node_targets = merged_targets;
+            self.unsorted_edges.clear();
         }
+        self.node_sources.take();
+        for (source, target, weight) in edges {
+            // Once any edge is unsorted, later edges follow it to stay after it
+            // in their rows
+            if !self.unsorted_edges.is_empty()
+                || self.node_targets.append(source, target, weight).is_err()
+            {
+                self.unsorted_edges.push((source, target, weight));
+            }
+        }
+        Ok(())
+    }
 
-        let mut numbers = vec![0; chunk_buffer.len() / 2];
-        LittleEndian::read_u16_into(&chunk_buffer, &mut numbers);
-        debug!("Getting targets for node {}...", self.loading_node_index);
-        for &num in numbers.iter() {
-            // The MAX acts as a delimiter
-            if num == u16::MAX {
-                self.loading_node_index += 1;
-            } else {
-                let target_index = num as usize;
+    /// The targets with any unsorted edges merged in
+    fn target_adjacency(&self) -> &adjacency::Adjacency {
+        if self.unsorted_edges.is_empty() {
+            return &self.node_targets;
+        }
+        self.merged_targets
+            .get_or_init(|| self.merge_unsorted_edges())
+    }
 
-                if self.node_targets.len() < target_index {
-                    debug!("usize target index: {}", num);
-                    debug!("u16 target index: {}", num);
-                }
+    fn merge_unsorted_edges(&self) -> adjacency::Adjacency {
+        let mut edges: Vec<(u32, u32, f32)> = self
+            .node_targets
+            .edges()
+            .chain(self.unsorted_edges.iter().copied())
+            .collect();
+        adjacency::Adjacency::from_edges(self.node_count(), &mut edges)
+            .expect("Added edges should be in range")
+    }
+
+    /// Merges any unsorted edges into the targets, freeing the unsorted
+    /// edges and the merged copy kept for reads
+    pub fn compact_edges(&mut self) {
+        if self.unsorted_edges.is_empty() {
+            return;
+        }
+        self.node_targets = match self.merged_targets.take() {
+            Some(merged_targets) => merged_targets,
+            None => self.merge_unsorted_edges(),
+        };
+        self.unsorted_edges = Vec::new();
+    }
+
+    fn source_adjacency(&self) -> &adjacency::Adjacency {
+        self.node_sources
+            .get_or_init(|| self.target_adjacency().transpose())
+    }
+
+    fn seed_indices(&self, seeds: js_sys::Uint32Array) -> Result<Vec<usize>, JsValue> {
+        let seeds: Vec<usize> = seeds.to_vec().iter().map(|&seed| seed as usize).collect();
+        if seeds.is_empty() {
+            return Err(JsValue::from_str("At least one seed node is required"));
+        }
+        if let Some(seed) = seeds.iter().find(|&&seed| seed >= self.node_count()) {
+            return Err(JsValue::from_str(&format!(
+                "Seed node {} is out of range for {} nodes",
+                seed,
+                self.node_count()
+            )));
+        }
+        Ok(seeds)
+    }
 
-                self.node_targets
-                    .get_mut(self.loading_node_index)
-                    .unwrap()
-                    .push(target_index);
-                self.node_sources
-                    .get_mut(target_index)
-                    .unwrap()
-                    .push(self.loading_node_index);
-                self.edges_loaded += 1;
+    /// Number of nodes whose targets have all been loaded
+    pub fn complete_source_count(&self) -> usize {
+        self.edge_stream.node_index()
+    }
+
+    pub fn loading_progress(&self) -> f32 {
+        // Files with a header say how many edges to expect, which tracks
+        // progress more evenly than counting nodes
+        if let Some(header) = self.edge_stream.header() {
+            if header.edge_count > 0 {
+                return (self.edge_count() as f32 / header.edge_count as f32).min(1.0);
             }
         }
+        if self.node_count() == 0 {
+            return 1.0;
+        }
+        (self.edge_stream.node_index() as f32 / self.node_count() as f32).min(1.0)
+    }
+
+    pub fn node_locations(&self) -> &geometry::Points {
+        &self.node_locations
+    }
+
+    pub fn node_location(&self, node_index: usize) -> geometry::Vector2 {
+        self.node_locations.get_point(node_index)
+    }
+
+    pub fn set_node_location(&mut self, node_index: usize, location: geometry::Vector2) {
+        self.move_nodes([(node_index, location)]);
+    }
+
+    /// Sets the location of each (node, location) pair, counting as a single
+    /// move for spatial indexes
+    pub fn move_nodes(&mut self, moves: impl IntoIterator<Item = (usize, geometry::Vector2)>) {
+        for (node_index, location) in moves {
+            self.node_locations.set_point(node_index, location);
+        }
+        self.locations_revision += 1;
+    }
+
+    /// Replaces every node's location with interleaved x, y coordinates
+    pub fn set_node_locations(&mut self, locations: Vec<f32>) {
+        self.node_locations = geometry::Points::new(locations);
+        self.locations_revision += 1;
+    }
+
+    pub fn locations_revision(&self) -> u64 {
+        self.locations_revision
+    }
+
+    /// Pinned nodes keep their location during force-directed layout
+    ///
+    /// Nodes that do not exist are ignored.
+    pub fn set_pinned(&mut self, node_index: usize, pinned: bool) {
+        if let Some(node_pinned) = self.pinned.get_mut(node_index) {
+            *node_pinned = pinned;
+        }
+    }
+
+    pub fn is_pinned(&self, node_index: usize) -> bool {
+        self.pinned.get(node_index).copied().unwrap_or(false)
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.node_targets.node_count()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.node_targets.edge_count() + self.unsorted_edges.len()
+    }
+
+    pub fn targets(&self, node_index: usize) -> &[u32] {
+        self.target_adjacency().neighbours(node_index)
+    }
+
+    /// Sources of the edges to the node, in ascending order
+    pub fn sources(&self, node_index: usize) -> &[u32] {
+        self.source_adjacency().neighbours(node_index)
+    }
+
+    /// Weights of the edges to each of `targets(node_index)`, in the same order
+    pub fn target_weights(&self, node_index: usize) -> &[f32] {
+        self.target_adjacency().weights(node_index)
+    }
+
+    /// Weights of the edges from each of `sources(node_index)`, in the same order
+    pub fn source_weights(&self, node_index: usize) -> &[f32] {
+        self.source_adjacency().weights(node_index)
+    }
+}
+
+/// A focus node that is not in the graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FocusOutOfRange {
+    pub focus_node: usize,
+    pub node_count: usize,
+}
+
+impl fmt::Display for FocusOutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "focus node {} is out of range for {} nodes",
+            self.focus_node, self.node_count
+        )
+    }
+}
+
+impl From<FocusOutOfRange> for JsValue {
+    fn from(error: FocusOutOfRange) -> JsValue {
+        js_sys::Error::new(&format!("Failed to display graph: {}", error)).into()
     }
 }
 
@@ -188,13 +930,24 @@ pub struct GraphDisplay {
     prev_touch: Option<input::TouchSet>,
     clipspace_locations: geometry::Points,
     clipspace_vertices: Vec<f32>,
-    vertex_indices: Vec<u16>,
+    // Index over layout locations, built when first queried after nodes
+    // move, and the part of the layout shown when the clipspace locations
+    // were last updated
+    spatial_index: OnceCell<quadtree::QuadTree>,
+    spatial_index_revision: u64,
+    visible_bounds: geometry::Rect,
+    vertex_indices: Vec<u32>,
+    edge_band_offsets: Vec<u32>,
     autopanning: bool,
     autopan_dest: geometry::Vector2,
     autopan_rate_mul: f32,
+    // The node being dragged and whether it was pinned before the drag
+    dragged: Option<(usize, bool)>,
 }
 
 impl GraphDisplay {
+    /// Fails when the focus node does not exist, unless the graph is empty,
+    /// in which case the display is centred on the origin
     pub fn new(
         layout: GraphLayout,
         display_width: f32,
@@ -202,9 +955,19 @@ impl GraphDisplay {
         display_scale: f32,
         autopan_rate_mul: f32,
         focus_node_idx: usize,
-    ) -> GraphDisplay {
+    ) -> Result<GraphDisplay, FocusOutOfRange> {
+        let node_count = layout.node_count();
+        let display_offset = if node_count == 0 {
+            geometry::Vector2::new(0.0, 0.0)
+        } else if focus_node_idx < node_count {
+            layout.node_locations.get_point(focus_node_idx)
+        } else {
+            return Err(FocusOutOfRange {
+                focus_node: focus_node_idx,
+                node_count,
+            });
+        };
         let aspect_ratio = display_width / display_height;
-        let display_offset = layout.node_locations.get_point(focus_node_idx);
         let prev_touch = None;
         let current_touches = None;
         let clipspace_locations =
@@ -212,10 +975,14 @@ impl GraphDisplay {
                 .node_locations
                 .to_clipspace(display_offset, &display_scale, &aspect_ratio);
         let clipspace_vertices = clipspace_locations.get_data();
-        let vertex_indices: Vec<u16> = Vec::new();
+        let spatial_index = OnceCell::new();
+        let spatial_index_revision = layout.locations_revision();
+        let visible_bounds =
+            GraphDisplay::layout_bounds(display_offset, display_scale, aspect_ratio);
+        let vertex_indices: Vec<u32> = Vec::new();
         let autopanning = false;
         let autopan_dest = display_offset;
-        GraphDisplay {
+        Ok(GraphDisplay {
             layout,
             display_width,
             display_height,
@@ -225,18 +992,23 @@ impl GraphDisplay {
             prev_touch,
             clipspace_locations,
             clipspace_vertices,
+            spatial_index,
+            spatial_index_revision,
+            visible_bounds,
             vertex_indices,
+            edge_band_offsets: vec![0; EDGE_ALPHA_BANDS + 1],
             autopanning,
             autopan_dest,
             autopan_rate_mul,
-        }
+            dragged: None,
+        })
     }
 
     pub fn get_vertices_ptr(&self) -> *const f32 {
         self.clipspace_vertices.as_ptr()
     }
 
-    pub fn get_vertex_indices_ptr(&self) -> *const u16 {
+    pub fn get_vertex_indices_ptr(&self) -> *const u32 {
         self.vertex_indices.as_ptr()
     }
 
@@ -245,43 +1017,100 @@ impl GraphDisplay {
     }
 
     pub fn get_visible_node_page_locations(&self) -> Result<JsValue, JsValue> {
-        let mut locations: HashMap<usize, geometry::Vector2> = HashMap::new();
-        for (node_id, loc) in self.clipspace_locations.iter().enumerate() {
-            if CLIPSPACE_BOUNDS.contains(loc) {
-                let x = ((loc.x + 1.0) / 2.0) * self.display_width;
-                let y = self.display_height - (((loc.y + 1.0) / 2.0) * self.display_height);
-                locations.insert(node_id, geometry::Vector2 { x, y });
-            }
-        }
+        let locations: HashMap<usize, geometry::Vector2> =
+            self.visible_node_page_locations().into_iter().collect();
+        Ok(serde_wasm_bindgen::to_value(&locations)?)
+    }
+
+    /// Returns the page location of every node on screen, in node order
+    fn visible_node_page_locations(&self) -> Vec<(usize, geometry::Vector2)> {
+        let mut locations = Vec::new();
+        // The index narrows the search to nodes near the screen, then the
+        // clipspace locations decide exactly which are on it
+        self.spatial_index()
+            .visit_within(&self.visible_bounds, |node_id| {
+                let loc = self.clipspace_locations.get_point(node_id);
+                if CLIPSPACE_BOUNDS.contains(loc) {
+                    let page_loc =
+                        geometry::clipspace_to_page(loc, self.display_width, self.display_height);
+                    locations.push((node_id, page_loc));
+                }
+            });
+        locations.sort_by_key(|&(node_id, _)| node_id);
+        locations
+    }
+
+    /// Returns the page locations of the visible nodes whose labels fit on
+    /// screen without overlapping, giving way to nodes earlier in `priority`
+    ///
+    /// `label_widths` holds the estimated width of every node's label.
+    pub fn get_labelled_node_page_locations<K: Ord>(
+        &self,
+        label_widths: &[f32],
+        label_height: f32,
+        max_labels: usize,
+        priority: impl Fn(usize) -> K,
+    ) -> Result<JsValue, JsValue> {
+        let mut candidates: Vec<labels::Label> = self
+            .visible_node_page_locations()
+            .into_iter()
+            .filter_map(|(node_id, page_location)| {
+                label_widths.get(node_id).map(|&width| labels::Label {
+                    node: node_id,
+                    page_location,
+                    width,
+                    height: label_height,
+                })
+            })
+            .collect();
+        candidates.sort_by_key(|label| priority(label.node));
+        let locations: HashMap<usize, geometry::Vector2> =
+            labels::place_labels(&candidates, max_labels)
+                .into_iter()
+                .map(|label| (label.node, label.page_location))
+                .collect();
         Ok(serde_wasm_bindgen::to_value(&locations)?)
     }
 
     pub fn count_edges(&self) -> usize {
-        self.layout
-            .node_targets
-            .iter()
-            .map(|targets| targets.len())
-            .sum()
+        self.layout.edge_count()
     }
 
     pub fn update_edges(&mut self) {
         // Ensure that the indices used for drawing edges are up-to-date
         let edges_count = self.count_edges();
         if edges_count > (self.vertex_indices.len() / 2) {
-            self.vertex_indices.resize(edges_count * 2, u16::MAX);
-            let mut edge_start_index = 0;
-            for (source_index, target_indices) in self.layout.node_targets.iter().enumerate() {
-                for target_index in target_indices {
-                    self.vertex_indices[edge_start_index] =
-                        u16::try_from(source_index).expect("Node index should fit u16");
-                    self.vertex_indices[edge_start_index + 1] =
-                        u16::try_from(*target_index).expect("Node index should fit u16");
-                    edge_start_index += 2;
+            let max_weight = (0..self.layout.node_count())
+                .flat_map(|node_index| self.layout.target_weights(node_index))
+                .fold(0.0, |max: f32, &weight| max.max(weight));
+            // Edges are grouped into bands of equal alpha so each band can
+            // be drawn with a single call
+            let mut bands: Vec<Vec<u32>> = vec![Vec::new(); EDGE_ALPHA_BANDS];
+            for source_index in 0..self.layout.node_count() {
+                let targets = self.layout.targets(source_index);
+                let weights = self.layout.target_weights(source_index);
+                for (&target_index, &weight) in targets.iter().zip(weights) {
+                    bands[edge_alpha_band(weight, max_weight)].extend([
+                        u32::try_from(source_index).expect("Node index should fit u32"),
+                        target_index,
+                    ]);
                 }
             }
+            self.vertex_indices.clear();
+            self.edge_band_offsets.clear();
+            self.edge_band_offsets.push(0);
+            for band in bands {
+                self.vertex_indices.extend(band);
+                self.edge_band_offsets
+                    .push(self.vertex_indices.len() as u32);
+            }
         }
     }
 
+    pub fn get_edge_band_offsets(&self) -> &[u32] {
+        &self.edge_band_offsets
+    }
+
     pub fn update_display_size(&mut self, display_width: f32, display_height: f32) {
         self.display_width = display_width;
         self.display_height = display_height;
@@ -296,6 +1125,40 @@ impl GraphDisplay {
             &aspect_ratio,
         );
         self.clipspace_vertices = self.clipspace_locations.get_data();
+        self.visible_bounds =
+            GraphDisplay::layout_bounds(self.display_offset, self.display_scale, aspect_ratio);
+        if self.spatial_index_revision != self.layout.locations_revision() {
+            self.spatial_index.take();
+            self.spatial_index_revision = self.layout.locations_revision();
+        }
+    }
+
+    /// Builds the index when first queried after nodes move
+    fn spatial_index(&self) -> &quadtree::QuadTree {
+        self.spatial_index.get_or_init(|| {
+            let masses = vec![1.0; self.layout.node_count()];
+            quadtree::QuadTree::new(self.layout.node_locations(), &masses)
+        })
+    }
+
+    /// The part of the layout that maps to clipspace
+    fn layout_bounds(
+        display_offset: geometry::Vector2,
+        display_scale: f32,
+        aspect_ratio: f32,
+    ) -> geometry::Rect {
+        let corner = |clipspace_corner: geometry::Vector2| {
+            geometry::clipspace_to_layout(
+                clipspace_corner,
+                display_offset,
+                display_scale,
+                aspect_ratio,
+            )
+        };
+        geometry::Rect::new(
+            corner(CLIPSPACE_BOUNDS.bottom_left),
+            corner(CLIPSPACE_BOUNDS.top_right),
+        )
     }
 
     pub fn pan(&mut self, x: f32, y: f32) {
@@ -313,6 +1176,15 @@ impl GraphDisplay {
         self.display_scale /= DISPLAY_ZOOM_RATE;
     }
 
+    pub fn zoom_at(&mut self, page_x: f32, page_y: f32, factor: f32) {
+        if !(factor > 0.0 && factor.is_finite()) {
+            return;
+        }
+        let anchor = self.page_to_layout(geometry::Vector2::new(page_x, page_y));
+        self.display_scale *= factor;
+        self.display_offset = geometry::zoom_offset(self.display_offset, anchor, factor);
+    }
+
     pub fn touch_start(&mut self, touch: input::TouchSet) {
         self.autopanning = false;
         self.prev_touch = None;
@@ -350,16 +1222,24 @@ impl GraphDisplay {
                 return;
             }
 
-            let pinch: f32 = touches.as_slice().windows(2).map(input::pinch_diff).sum();
-            if pinch > 0.0 {
-                self.display_scale *= DISPLAY_ZOOM_RATE_PINCH;
-            } else if pinch < 0.0 {
-                self.display_scale /= DISPLAY_ZOOM_RATE_PINCH;
-            }
+            // Panning first moves the layout point under the fingers along
+            // with them, so zooming around the centroid then keeps it there
             let offset_addend: geometry::Vector2 =
                 touches.as_slice().windows(2).map(input::touch_offset).sum();
             self.display_offset += offset_addend.flip_y() * self.get_pan_rate();
 
+            // The display scales with the distance between the fingers,
+            // across every move since the last update
+            let factor: f32 = touches
+                .as_slice()
+                .windows(2)
+                .map(input::pinch_ratio)
+                .product();
+            let latest_touch = touches.last().expect("Touches should not be empty");
+            if let Some(centroid) = input::touch_centroid(latest_touch) {
+                self.zoom_at(centroid.x, centroid.y, factor);
+            }
+
             self.prev_touch = touches.pop();
             self.current_touches = Some(Vec::new());
         }
@@ -373,6 +1253,80 @@ impl GraphDisplay {
         ((DISPLAY_PAN_RATE * 2.0) / self.display_scale) / self.display_height
     }
 
+    pub fn pick_node(&self, page_x: f32, page_y: f32) -> Option<usize> {
+        self.node_at(page_x, page_y, PICK_RADIUS_PX)
+    }
+
+    /// Nearest node drawn within `radius_px` page pixels of the page location
+    pub fn node_at(&self, page_x: f32, page_y: f32, radius_px: f32) -> Option<usize> {
+        let page_loc = geometry::Vector2::new(page_x, page_y);
+        let layout_radius =
+            geometry::page_distance_to_layout(radius_px, self.display_height, self.display_scale);
+        let layout_loc = self.page_to_layout(page_loc);
+        let radius = geometry::Vector2::new(layout_radius, layout_radius);
+        let search_bounds = geometry::Rect::new(layout_loc - radius, layout_loc + radius);
+
+        let mut nearest: Option<(usize, f32)> = None;
+        self.spatial_index()
+            .visit_within(&search_bounds, |node_id| {
+                let loc = self.clipspace_locations.get_point(node_id);
+                let node_page_loc =
+                    geometry::clipspace_to_page(loc, self.display_width, self.display_height);
+                let distance = (node_page_loc - page_loc).magnitude();
+                let is_nearer =
+                    nearest.is_none_or(|(_, nearest_distance)| distance < nearest_distance);
+                if distance <= radius_px && is_nearer {
+                    nearest = Some((node_id, distance));
+                }
+            });
+        nearest.map(|(node_id, _)| node_id)
+    }
+
+    /// Ignores nodes that do not exist
+    pub fn drag_node(&mut self, node_id: usize, page_x: f32, page_y: f32) {
+        if node_id >= self.layout.node_count() {
+            return;
+        }
+        self.autopanning = false;
+        if self
+            .dragged
+            .is_none_or(|(dragged_id, _)| dragged_id != node_id)
+        {
+            self.release_dragged();
+            self.dragged = Some((node_id, self.layout.is_pinned(node_id)));
+        }
+        let layout_loc = self.page_to_layout(geometry::Vector2::new(page_x, page_y));
+        self.layout.set_node_location(node_id, layout_loc);
+        self.layout.set_pinned(node_id, true);
+    }
+
+    pub fn release_node(&mut self, node_id: usize) {
+        if self
+            .dragged
+            .is_some_and(|(dragged_id, _)| dragged_id == node_id)
+        {
+            self.release_dragged();
+        }
+    }
+
+    /// Restores whether the dragged node was pinned before the drag
+    fn release_dragged(&mut self) {
+        if let Some((node_id, was_pinned)) = self.dragged.take() {
+            self.layout.set_pinned(node_id, was_pinned);
+        }
+    }
+
+    fn page_to_layout(&self, page_loc: geometry::Vector2) -> geometry::Vector2 {
+        let clipspace_loc =
+            geometry::page_to_clipspace(page_loc, self.display_width, self.display_height);
+        geometry::clipspace_to_layout(
+            clipspace_loc,
+            self.display_offset,
+            self.display_scale,
+            self.get_aspect_ratio(),
+        )
+    }
+
     pub fn autopan(&mut self, node_id: usize) {
         self.autopan_dest = self.layout.node_locations.get_point(node_id);
         self.autopanning = true
@@ -382,3 +1336,6 @@ impl GraphDisplay {
         self.autopanning
     }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,727 @@
+//! Streaming decoder for the edge data in targets.bin
+//!
+//! A self-describing file has the following layout, with all integers
+//! little-endian:
+//!
+//! | Offset | Size | Field                                        |
+//! |--------|------|----------------------------------------------|
+//! | 0      | 4    | Magic number `89 52 57 47` (`\x89RWG`)       |
+//! | 4      | 2    | Format version, currently 1                  |
+//! | 6      | 1    | Index width in bytes, 2 or 4, or 0 for varint |
+//! | 7      | 1    | Flags; bit 0 set when edges are weighted     |
+//! | 8      | 4    | Node count                                   |
+//! | 12     | 4    | Edge count                                   |
+//! | 16     | ...  | Edge stream                                  |
+//! | end    | 4    | CRC-32 of every preceding byte               |
+//!
+//! The edge stream holds each node's target indices in node order, followed
+//! by the maximum value of the index type as a delimiter. In weighted files
+//! each target index is immediately followed by its edge weight as an f32.
+//!
+//! With an index width of 0 the stream is compressed instead: each node
+//! starts with its target count as an unsigned LEB128 varint, followed by its
+//! sorted targets as varint gaps from the previous target, the first gap
+//! being taken from zero. Weights follow each gap as in the fixed-width
+//! stream.
+//!
+//! Files without the magic number are read as a bare edge stream of the
+//! configured `EdgeEncoding`, so older files keep working.
+
+use byteorder::{ByteOrder, LittleEndian};
+use log::debug;
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+pub const MAGIC: [u8; 4] = [0x89, b'R', b'W', b'G'];
+pub const FORMAT_VERSION: u16 = 1;
+pub const HEADER_LEN: usize = 16;
+pub const FLAG_WEIGHTED: u8 = 0b0000_0001;
+const CHECKSUM_LEN: usize = 4;
+const WEIGHT_LEN: usize = 4;
+const MAX_VARINT_LEN: usize = 5;
+
+/// Width of the target indices in the edge stream
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeEncoding {
+    /// u16 target indices, limiting graphs to 65,535 nodes
+    U16 = 1,
+    /// u32 target indices
+    U32 = 2,
+    /// Gap-encoded LEB128 varint target indices, prefixed by a target count
+    Varint = 3,
+}
+
+impl EdgeEncoding {
+    pub fn from_index_width(index_width: u8) -> Option<EdgeEncoding> {
+        match index_width {
+            2 => Some(EdgeEncoding::U16),
+            4 => Some(EdgeEncoding::U32),
+            0 => Some(EdgeEncoding::Varint),
+            _ => None,
+        }
+    }
+
+    /// Zero for the variable-length encoding
+    pub fn index_width(self) -> usize {
+        self.fixed_width().map_or(0, FixedWidth::index_width)
+    }
+
+    /// The index type of a fixed-width encoding, or `None` for varints
+    fn fixed_width(self) -> Option<FixedWidth> {
+        match self {
+            EdgeEncoding::U16 => Some(FixedWidth::U16),
+            EdgeEncoding::U32 => Some(FixedWidth::U32),
+            EdgeEncoding::Varint => None,
+        }
+    }
+}
+
+/// Index type of an edge stream with delimited, fixed-width target indices
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FixedWidth {
+    U16,
+    U32,
+}
+
+impl FixedWidth {
+    fn index_width(self) -> usize {
+        match self {
+            FixedWidth::U16 => 2,
+            FixedWidth::U32 => 4,
+        }
+    }
+
+    fn read_index(self, bytes: &[u8]) -> u32 {
+        match self {
+            FixedWidth::U16 => LittleEndian::read_u16(bytes) as u32,
+            FixedWidth::U32 => LittleEndian::read_u32(bytes),
+        }
+    }
+
+    fn delimiter(self) -> u32 {
+        match self {
+            FixedWidth::U16 => u16::MAX as u32,
+            FixedWidth::U32 => u32::MAX,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub version: u16,
+    pub encoding: EdgeEncoding,
+    pub node_count: usize,
+    pub edge_count: usize,
+    pub weighted: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadErrorKind {
+    UnsupportedVersion(u16),
+    UnsupportedIndexWidth(u8),
+    UnsupportedFlags(u8),
+    NodeCountMismatch { expected: usize, found: usize },
+    EdgeCountMismatch { expected: usize, found: usize },
+    ChecksumMismatch { expected: u32, computed: u32 },
+    TargetOutOfRange { target: usize, node_count: usize },
+    TooManyDelimiters { node_count: usize },
+    EdgeAfterLastNode { node_count: usize },
+    InvalidWeight { weight: f32 },
+    InvalidVarint,
+    TrailingData,
+    Truncated,
+}
+
+/// Why the edge stream could not be loaded, and where it went wrong
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadError {
+    pub offset: usize,
+    pub node_index: usize,
+    pub kind: LoadErrorKind,
+}
+
+impl fmt::Display for LoadErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadErrorKind::UnsupportedVersion(version) => {
+                write!(f, "unsupported format version {}", version)
+            }
+            LoadErrorKind::UnsupportedIndexWidth(width) => {
+                write!(f, "unsupported index width of {} bytes", width)
+            }
+            LoadErrorKind::UnsupportedFlags(flags) => {
+                write!(f, "unsupported flags {:#04x}", flags)
+            }
+            LoadErrorKind::NodeCountMismatch { expected, found } => {
+                write!(f, "file has {} nodes but {} were expected", found, expected)
+            }
+            LoadErrorKind::EdgeCountMismatch { expected, found } => {
+                write!(
+                    f,
+                    "header declares {} edges but {} were read",
+                    expected, found
+                )
+            }
+            LoadErrorKind::ChecksumMismatch { expected, computed } => write!(
+                f,
+                "checksum {:#010x} does not match computed {:#010x}",
+                expected, computed
+            ),
+            LoadErrorKind::TargetOutOfRange { target, node_count } => write!(
+                f,
+                "target {} is out of range for {} nodes",
+                target, node_count
+            ),
+            LoadErrorKind::TooManyDelimiters { node_count } => {
+                write!(f, "more node delimiters than the {} nodes", node_count)
+            }
+            LoadErrorKind::EdgeAfterLastNode { node_count } => {
+                write!(f, "edge after the last of the {} nodes", node_count)
+            }
+            LoadErrorKind::InvalidWeight { weight } => {
+                write!(
+                    f,
+                    "edge weight {} is not a finite, non-negative number",
+                    weight
+                )
+            }
+            LoadErrorKind::InvalidVarint => write!(f, "varint does not fit in 32 bits"),
+            LoadErrorKind::TrailingData => write!(f, "unexpected data after checksum"),
+            LoadErrorKind::Truncated => write!(f, "file ended early"),
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Failed to load edges: {} at byte {} (node {})",
+            self.kind, self.offset, self.node_index
+        )
+    }
+}
+
+impl LoadErrorKind {
+    /// Short identifier for the kind of error, for handling in JavaScript
+    pub fn reason(&self) -> &'static str {
+        match self {
+            LoadErrorKind::UnsupportedVersion(_) => "unsupported_version",
+            LoadErrorKind::UnsupportedIndexWidth(_) => "unsupported_index_width",
+            LoadErrorKind::UnsupportedFlags(_) => "unsupported_flags",
+            LoadErrorKind::NodeCountMismatch { .. } => "node_count_mismatch",
+            LoadErrorKind::EdgeCountMismatch { .. } => "edge_count_mismatch",
+            LoadErrorKind::ChecksumMismatch { .. } => "checksum_mismatch",
+            LoadErrorKind::TargetOutOfRange { .. } => "target_out_of_range",
+            LoadErrorKind::TooManyDelimiters { .. } => "too_many_delimiters",
+            LoadErrorKind::EdgeAfterLastNode { .. } => "edge_after_last_node",
+            LoadErrorKind::InvalidWeight { .. } => "invalid_weight",
+            LoadErrorKind::InvalidVarint => "invalid_varint",
+            LoadErrorKind::TrailingData => "trailing_data",
+            LoadErrorKind::Truncated => "truncated",
+        }
+    }
+}
+
+/// Converts to a JavaScript `Error` with `offset`, `nodeIndex` and `reason`
+/// properties alongside the message
+impl From<LoadError> for JsValue {
+    fn from(error: LoadError) -> JsValue {
+        let js_error = js_sys::Error::new(&error.to_string());
+        let properties = [
+            ("offset", JsValue::from(error.offset as f64)),
+            ("nodeIndex", JsValue::from(error.node_index as f64)),
+            ("reason", JsValue::from_str(error.kind.reason())),
+        ];
+        for (key, value) in properties {
+            js_sys::Reflect::set(&js_error, &JsValue::from_str(key), &value)
+                .expect("Setting a property on an Error should succeed");
+        }
+        js_error.into()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    Detecting,
+    Header,
+    Body,
+    Checksum,
+    Complete,
+}
+
+/// Decodes an edge stream that arrives in arbitrarily sized chunks
+pub struct EdgeStream {
+    encoding: EdgeEncoding,
+    node_count: usize,
+    header: Option<Header>,
+    stage: Stage,
+    node_index: usize,
+    edges_decoded: usize,
+    // Targets left for the current node of a varint stream, once its count is read
+    targets_remaining: Option<usize>,
+    previous_target: usize,
+    bytes_received: usize,
+    // Absolute offset of the first byte of `pending`
+    offset: usize,
+    pending: Vec<u8>,
+    checksum: Crc32,
+    failure: Option<LoadError>,
+}
+
+impl EdgeStream {
+    /// `encoding` is only used for files without a header
+    pub fn new(encoding: EdgeEncoding, node_count: usize) -> EdgeStream {
+        EdgeStream {
+            encoding,
+            node_count,
+            header: None,
+            stage: Stage::Detecting,
+            node_index: 0,
+            edges_decoded: 0,
+            targets_remaining: None,
+            previous_target: 0,
+            bytes_received: 0,
+            offset: 0,
+            pending: Vec::new(),
+            checksum: Crc32::new(),
+            failure: None,
+        }
+    }
+
+    /// Index of the node whose targets are currently being read
+    pub fn node_index(&self) -> usize {
+        self.node_index
+    }
+
+    pub fn bytes_received(&self) -> usize {
+        self.bytes_received
+    }
+
+    pub fn header(&self) -> Option<&Header> {
+        self.header.as_ref()
+    }
+
+    /// Returns the (source, target, weight) edges completed by this chunk
+    ///
+    /// Edges from files without weights have a weight of one.
+    ///
+    /// Bytes of a field split across chunks are kept until the next chunk.
+    ///
+    /// Once an error has been returned the stream stays failed, so every
+    /// later chunk returns the same error.
+    pub fn decode(&mut self, chunk: &[u8]) -> Result<Vec<(usize, usize, f32)>, LoadError> {
+        if let Some(failure) = &self.failure {
+            return Err(failure.clone());
+        }
+        let result = self.decode_chunk(chunk);
+        if let Err(error) = &result {
+            self.failure = Some(error.clone());
+        }
+        result
+    }
+
+    fn decode_chunk(&mut self, chunk: &[u8]) -> Result<Vec<(usize, usize, f32)>, LoadError> {
+        self.bytes_received += chunk.len();
+        let mut pending = std::mem::take(&mut self.pending);
+        pending.extend_from_slice(chunk);
+
+        debug!("Getting targets for node {}...", self.node_index);
+        let mut edges = Vec::new();
+        let mut position = 0;
+        loop {
+            let bytes = &pending[position..];
+            let consumed = match self.stage {
+                Stage::Detecting => self.detect(bytes),
+                Stage::Header => self.read_header(bytes)?,
+                Stage::Body => self.read_body(bytes, &mut edges)?,
+                Stage::Checksum => self.read_checksum(bytes)?,
+                Stage::Complete => self.read_trailing(bytes)?,
+            };
+            match consumed {
+                Some(len) => {
+                    position += len;
+                    self.offset += len;
+                }
+                None => break,
+            }
+        }
+        self.pending = pending.split_off(position);
+        Ok(edges)
+    }
+
+    /// Checks that the stream ended where the file says it should
+    pub fn finish(&self) -> Result<(), LoadError> {
+        if let Some(failure) = &self.failure {
+            return Err(failure.clone());
+        }
+        let complete = match self.stage {
+            Stage::Complete => true,
+            Stage::Detecting | Stage::Body => {
+                self.header.is_none() && self.pending.is_empty() && self.targets_remaining.is_none()
+            }
+            Stage::Header | Stage::Checksum => false,
+        };
+        if complete {
+            Ok(())
+        } else {
+            Err(self.error_at(self.bytes_received, LoadErrorKind::Truncated))
+        }
+    }
+
+    fn error_at(&self, offset: usize, kind: LoadErrorKind) -> LoadError {
+        LoadError {
+            offset,
+            node_index: self.node_index,
+            kind,
+        }
+    }
+
+    fn detect(&mut self, bytes: &[u8]) -> Option<usize> {
+        let compared_len = bytes.len().min(MAGIC.len());
+        if bytes[..compared_len] != MAGIC[..compared_len] {
+            self.stage = Stage::Body;
+            Some(0)
+        } else if compared_len == MAGIC.len() {
+            self.stage = Stage::Header;
+            Some(0)
+        } else {
+            None
+        }
+    }
+
+    fn read_header(&mut self, bytes: &[u8]) -> Result<Option<usize>, LoadError> {
+        if bytes.len() < HEADER_LEN {
+            return Ok(None);
+        }
+        let version = LittleEndian::read_u16(&bytes[4..6]);
+        if version != FORMAT_VERSION {
+            return Err(self.error_at(self.offset + 4, LoadErrorKind::UnsupportedVersion(version)));
+        }
+        let index_width = bytes[6];
+        let encoding = EdgeEncoding::from_index_width(index_width).ok_or_else(|| {
+            self.error_at(
+                self.offset + 6,
+                LoadErrorKind::UnsupportedIndexWidth(index_width),
+            )
+        })?;
+        let flags = bytes[7];
+        if flags & !FLAG_WEIGHTED != 0 {
+            return Err(self.error_at(self.offset + 7, LoadErrorKind::UnsupportedFlags(flags)));
+        }
+        let node_count = LittleEndian::read_u32(&bytes[8..12]) as usize;
+        if node_count != self.node_count {
+            return Err(self.error_at(
+                self.offset + 8,
+                LoadErrorKind::NodeCountMismatch {
+                    expected: self.node_count,
+                    found: node_count,
+                },
+            ));
+        }
+        let edge_count = LittleEndian::read_u32(&bytes[12..16]) as usize;
+
+        self.checksum.update(&bytes[..HEADER_LEN]);
+        self.encoding = encoding;
+        self.header = Some(Header {
+            version,
+            encoding,
+            node_count,
+            edge_count,
+            weighted: flags & FLAG_WEIGHTED != 0,
+        });
+        self.stage = if node_count == 0 {
+            Stage::Checksum
+        } else {
+            Stage::Body
+        };
+        Ok(Some(HEADER_LEN))
+    }
+
+    fn read_body(
+        &mut self,
+        bytes: &[u8],
+        edges: &mut Vec<(usize, usize, f32)>,
+    ) -> Result<Option<usize>, LoadError> {
+        let consumed = match self.encoding.fixed_width() {
+            Some(width) => self.read_fixed_body(width, bytes, edges)?,
+            None => self.read_varint_body(bytes, edges)?,
+        };
+        self.checksum.update(&bytes[..consumed]);
+
+        if let Some(header) = &self.header {
+            if self.stage == Stage::Checksum && self.edges_decoded != header.edge_count {
+                return Err(self.error_at(
+                    self.offset + consumed,
+                    LoadErrorKind::EdgeCountMismatch {
+                        expected: header.edge_count,
+                        found: self.edges_decoded,
+                    },
+                ));
+            }
+        }
+        if consumed == 0 {
+            Ok(None)
+        } else {
+            Ok(Some(consumed))
+        }
+    }
+
+    fn read_fixed_body(
+        &mut self,
+        width: FixedWidth,
+        bytes: &[u8],
+        edges: &mut Vec<(usize, usize, f32)>,
+    ) -> Result<usize, LoadError> {
+        let index_width = width.index_width();
+        let weight_len = self.weight_len();
+        let weighted = weight_len > 0;
+        let mut consumed = 0;
+        while bytes.len() - consumed >= index_width {
+            let index_offset = self.offset + consumed;
+            let index_bytes = &bytes[consumed..consumed + index_width];
+            let num = width.read_index(index_bytes);
+            // Every index, including a delimiter, belongs to the current node
+            if self.node_index >= self.node_count {
+                let node_count = self.node_count;
+                let kind = if num == width.delimiter() {
+                    LoadErrorKind::TooManyDelimiters { node_count }
+                } else {
+                    LoadErrorKind::EdgeAfterLastNode { node_count }
+                };
+                return Err(self.error_at(index_offset, kind));
+            }
+            // The MAX acts as a delimiter
+            if num == width.delimiter() {
+                consumed += index_width;
+                self.node_index += 1;
+                if self.body_complete() {
+                    self.stage = Stage::Checksum;
+                    break;
+                }
+            } else {
+                let target = num as usize;
+                if target >= self.node_count {
+                    return Err(self.error_at(
+                        index_offset,
+                        LoadErrorKind::TargetOutOfRange {
+                            target,
+                            node_count: self.node_count,
+                        },
+                    ));
+                }
+                if bytes.len() - consumed < index_width + weight_len {
+                    // Wait for the rest of the weight in the next chunk
+                    break;
+                }
+                let weight_start = consumed + index_width;
+                let weight = if weighted {
+                    self.read_weight(&bytes[weight_start..], self.offset + weight_start)?
+                } else {
+                    1.0
+                };
+                consumed += index_width + weight_len;
+                edges.push((self.node_index, target, weight));
+                self.edges_decoded += 1;
+            }
+        }
+        Ok(consumed)
+    }
+
+    fn read_varint_body(
+        &mut self,
+        bytes: &[u8],
+        edges: &mut Vec<(usize, usize, f32)>,
+    ) -> Result<usize, LoadError> {
+        let weight_len = self.weight_len();
+        let mut consumed = 0;
+        while consumed < bytes.len() {
+            let varint_offset = self.offset + consumed;
+            let Some(remaining) = self.targets_remaining else {
+                let Some((count, len)) = self.read_varint(&bytes[consumed..], varint_offset)?
+                else {
+                    break;
+                };
+                if self.node_index >= self.node_count {
+                    // A node past the end is an extra delimiter if it is
+                    // empty, otherwise it carries stray edges
+                    let node_count = self.node_count;
+                    let kind = if count == 0 {
+                        LoadErrorKind::TooManyDelimiters { node_count }
+                    } else {
+                        LoadErrorKind::EdgeAfterLastNode { node_count }
+                    };
+                    return Err(self.error_at(varint_offset, kind));
+                }
+                consumed += len;
+                self.targets_remaining = Some(count as usize);
+                self.previous_target = 0;
+                if self.finish_varint_node() {
+                    break;
+                }
+                continue;
+            };
+            let Some((gap, len)) = self.read_varint(&bytes[consumed..], varint_offset)? else {
+                break;
+            };
+            let target = self.previous_target.saturating_add(gap as usize);
+            if target >= self.node_count {
+                return Err(self.error_at(
+                    varint_offset,
+                    LoadErrorKind::TargetOutOfRange {
+                        target,
+                        node_count: self.node_count,
+                    },
+                ));
+            }
+            if bytes.len() - consumed < len + weight_len {
+                // Wait for the rest of the weight in the next chunk
+                break;
+            }
+            let weight_start = consumed + len;
+            let weight = if weight_len > 0 {
+                self.read_weight(&bytes[weight_start..], self.offset + weight_start)?
+            } else {
+                1.0
+            };
+            consumed += len + weight_len;
+            edges.push((self.node_index, target, weight));
+            self.edges_decoded += 1;
+            self.previous_target = target;
+            self.targets_remaining = Some(remaining - 1);
+            if self.finish_varint_node() {
+                break;
+            }
+        }
+        Ok(consumed)
+    }
+
+    /// Moves on to the next node once the current one has no targets left,
+    /// returning whether the body is complete
+    fn finish_varint_node(&mut self) -> bool {
+        if self.targets_remaining != Some(0) {
+            return false;
+        }
+        self.targets_remaining = None;
+        self.node_index += 1;
+        if self.body_complete() {
+            self.stage = Stage::Checksum;
+            return true;
+        }
+        false
+    }
+
+    /// Reads an unsigned LEB128 varint of at most 32 bits, returning the value
+    /// and its length, or `None` when the varint continues into the next chunk
+    fn read_varint(&self, bytes: &[u8], offset: usize) -> Result<Option<(u32, usize)>, LoadError> {
+        let mut value: u64 = 0;
+        for (index, &byte) in bytes.iter().enumerate().take(MAX_VARINT_LEN) {
+            value |= ((byte & 0x7F) as u64) << (7 * index);
+            if byte & 0x80 == 0 {
+                return match u32::try_from(value) {
+                    Ok(value) => Ok(Some((value, index + 1))),
+                    Err(_) => Err(self.error_at(offset, LoadErrorKind::InvalidVarint)),
+                };
+            }
+        }
+        if bytes.len() >= MAX_VARINT_LEN {
+            Err(self.error_at(offset, LoadErrorKind::InvalidVarint))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn read_weight(&self, bytes: &[u8], offset: usize) -> Result<f32, LoadError> {
+        let weight = LittleEndian::read_f32(&bytes[..WEIGHT_LEN]);
+        if !weight.is_finite() || weight < 0.0 {
+            return Err(self.error_at(offset, LoadErrorKind::InvalidWeight { weight }));
+        }
+        Ok(weight)
+    }
+
+    fn weight_len(&self) -> usize {
+        if self.header.as_ref().is_some_and(|header| header.weighted) {
+            WEIGHT_LEN
+        } else {
+            0
+        }
+    }
+
+    fn body_complete(&self) -> bool {
+        self.header
+            .as_ref()
+            .is_some_and(|header| self.node_index == header.node_count)
+    }
+
+    fn read_checksum(&mut self, bytes: &[u8]) -> Result<Option<usize>, LoadError> {
+        if bytes.len() < CHECKSUM_LEN {
+            return Ok(None);
+        }
+        let expected = LittleEndian::read_u32(&bytes[..CHECKSUM_LEN]);
+        let computed = self.checksum.value();
+        if expected != computed {
+            return Err(self.error_at(
+                self.offset,
+                LoadErrorKind::ChecksumMismatch { expected, computed },
+            ));
+        }
+        self.stage = Stage::Complete;
+        Ok(Some(CHECKSUM_LEN))
+    }
+
+    fn read_trailing(&self, bytes: &[u8]) -> Result<Option<usize>, LoadError> {
+        if bytes.is_empty() {
+            Ok(None)
+        } else {
+            Err(self.error_at(self.offset, LoadErrorKind::TrailingData))
+        }
+    }
+}
+
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut index = 0;
+    while index < 256 {
+        let mut value = index as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            value = if value & 1 == 1 {
+                0xEDB8_8320 ^ (value >> 1)
+            } else {
+                value >> 1
+            };
+            bit += 1;
+        }
+        table[index] = value;
+        index += 1;
+    }
+    table
+}
+
+/// CRC-32 (IEEE 802.3), as used by zlib and PNG
+pub struct Crc32 {
+    state: u32,
+}
+
+impl Crc32 {
+    pub fn new() -> Crc32 {
+        Crc32 { state: u32::MAX }
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            let table_index = ((self.state ^ byte as u32) & 0xFF) as usize;
+            self.state = CRC32_TABLE[table_index] ^ (self.state >> 8);
+        }
+    }
+
+    pub fn value(&self) -> u32 {
+        !self.state
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,504 @@
+use super::*;
+
+fn encode_u16(values: &[u16]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect()
+}
+
+fn encode_u32(values: &[u32]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect()
+}
+
+fn framed_file(node_targets: &[&[u32]], index_width: u8) -> Vec<u8> {
+    let weighted_targets: Vec<Vec<(u32, Option<f32>)>> = node_targets
+        .iter()
+        .map(|targets| targets.iter().map(|&target| (target, None)).collect())
+        .collect();
+    encode_file(&weighted_targets, index_width, 0)
+}
+
+fn weighted_file(node_targets: &[&[(u32, f32)]]) -> Vec<u8> {
+    let weighted_targets: Vec<Vec<(u32, Option<f32>)>> = node_targets
+        .iter()
+        .map(|targets| {
+            targets
+                .iter()
+                .map(|&(target, weight)| (target, Some(weight)))
+                .collect()
+        })
+        .collect();
+    encode_file(&weighted_targets, 2, FLAG_WEIGHTED)
+}
+
+fn encode_file(node_targets: &[Vec<(u32, Option<f32>)>], index_width: u8, flags: u8) -> Vec<u8> {
+    let edge_count: usize = node_targets.iter().map(|targets| targets.len()).sum();
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend(FORMAT_VERSION.to_le_bytes());
+    bytes.push(index_width);
+    bytes.push(flags);
+    bytes.extend((node_targets.len() as u32).to_le_bytes());
+    bytes.extend((edge_count as u32).to_le_bytes());
+    for targets in node_targets {
+        for &(target, weight) in targets.iter() {
+            match index_width {
+                2 => bytes.extend((target as u16).to_le_bytes()),
+                _ => bytes.extend(target.to_le_bytes()),
+            }
+            if let Some(weight) = weight {
+                bytes.extend(weight.to_le_bytes());
+            }
+        }
+        match index_width {
+            2 => bytes.extend(u16::MAX.to_le_bytes()),
+            _ => bytes.extend(u32::MAX.to_le_bytes()),
+        }
+    }
+    let mut checksum = Crc32::new();
+    checksum.update(&bytes);
+    bytes.extend(checksum.value().to_le_bytes());
+    bytes
+}
+
+fn encode_varint(mut value: u32, bytes: &mut Vec<u8>) {
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7F) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn encode_varint_body(node_targets: &[Vec<(u32, Option<f32>)>]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for targets in node_targets {
+        encode_varint(targets.len() as u32, &mut bytes);
+        let mut previous = 0;
+        for &(target, weight) in targets.iter() {
+            encode_varint(target - previous, &mut bytes);
+            previous = target;
+            if let Some(weight) = weight {
+                bytes.extend(weight.to_le_bytes());
+            }
+        }
+    }
+    bytes
+}
+
+/// Nodes beyond those in `node_targets` have no targets
+fn varint_file(node_targets: &[Vec<(u32, Option<f32>)>], node_count: usize, flags: u8) -> Vec<u8> {
+    let edge_count: usize = node_targets.iter().map(|targets| targets.len()).sum();
+    let mut node_targets = node_targets.to_vec();
+    node_targets.resize(node_count, Vec::new());
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend(FORMAT_VERSION.to_le_bytes());
+    bytes.push(0);
+    bytes.push(flags);
+    bytes.extend((node_count as u32).to_le_bytes());
+    bytes.extend((edge_count as u32).to_le_bytes());
+    bytes.extend(encode_varint_body(&node_targets));
+    let mut checksum = Crc32::new();
+    checksum.update(&bytes);
+    bytes.extend(checksum.value().to_le_bytes());
+    bytes
+}
+
+fn decode_all(stream: &mut EdgeStream, bytes: &[u8], chunk_len: usize) -> Vec<(usize, usize, f32)> {
+    let mut edges = Vec::new();
+    for chunk in bytes.chunks(chunk_len) {
+        edges.extend(stream.decode(chunk).unwrap());
+    }
+    edges
+}
+
+#[test]
+fn test_crc32_check_value() {
+    let mut checksum = Crc32::new();
+
+    checksum.update(b"123456789");
+
+    assert_eq!(checksum.value(), 0xCBF4_3926);
+}
+
+#[test]
+fn test_decode_u16_stream() {
+    let bytes = encode_u16(&[1, 2, u16::MAX, u16::MAX, 0, u16::MAX]);
+    let mut stream = EdgeStream::new(EdgeEncoding::U16, 3);
+
+    let edges = stream.decode(&bytes).unwrap();
+
+    assert_eq!(edges, vec![(0, 1, 1.0), (0, 2, 1.0), (2, 0, 1.0)]);
+    assert_eq!(stream.node_index(), 3);
+    assert_eq!(stream.finish(), Ok(()));
+}
+
+#[test]
+fn test_decode_u16_stream_split_mid_index() {
+    let bytes = encode_u16(&[1, u16::MAX, 0, u16::MAX]);
+    let mut stream = EdgeStream::new(EdgeEncoding::U16, 2);
+
+    let mut edges = stream.decode(&bytes[..3]).unwrap();
+    edges.extend(stream.decode(&bytes[3..5]).unwrap());
+    edges.extend(stream.decode(&bytes[5..]).unwrap());
+
+    assert_eq!(edges, vec![(0, 1, 1.0), (1, 0, 1.0)]);
+    assert_eq!(stream.node_index(), 2);
+    assert_eq!(stream.bytes_received(), 8);
+}
+
+#[test]
+fn test_decode_u32_stream_beyond_u16_range() {
+    let bytes = encode_u32(&[70_000, u32::MAX, 65_535, u32::MAX]);
+    let mut stream = EdgeStream::new(EdgeEncoding::U32, 70_001);
+
+    let edges = decode_all(&mut stream, &bytes, 3);
+
+    assert_eq!(edges, vec![(0, 70_000, 1.0), (1, 65_535, 1.0)]);
+    assert_eq!(stream.node_index(), 2);
+}
+
+#[test]
+fn test_decode_framed_file_byte_by_byte() {
+    let bytes = framed_file(&[&[1, 2], &[], &[0]], 2);
+    let mut stream = EdgeStream::new(EdgeEncoding::U32, 3);
+
+    let edges = decode_all(&mut stream, &bytes, 1);
+
+    assert_eq!(edges, vec![(0, 1, 1.0), (0, 2, 1.0), (2, 0, 1.0)]);
+    let header = stream.header().unwrap();
+    assert_eq!(header.encoding, EdgeEncoding::U16);
+    assert_eq!(header.edge_count, 3);
+    assert_eq!(stream.finish(), Ok(()));
+}
+
+#[test]
+fn test_decode_framed_file_with_u32_indices() {
+    let bytes = framed_file(&[&[1], &[0, 1]], 4);
+    let mut stream = EdgeStream::new(EdgeEncoding::U16, 2);
+
+    let edges = decode_all(&mut stream, &bytes, 5);
+
+    assert_eq!(edges, vec![(0, 1, 1.0), (1, 0, 1.0), (1, 1, 1.0)]);
+    assert_eq!(stream.finish(), Ok(()));
+}
+
+#[test]
+fn test_decode_framed_file_without_nodes() {
+    let bytes = framed_file(&[], 2);
+    let mut stream = EdgeStream::new(EdgeEncoding::U16, 0);
+
+    let edges = decode_all(&mut stream, &bytes, 7);
+
+    assert!(edges.is_empty());
+    assert_eq!(stream.finish(), Ok(()));
+}
+
+#[test]
+fn test_unsupported_version() {
+    let mut bytes = framed_file(&[&[1], &[]], 2);
+    bytes[4] = 9;
+    let mut stream = EdgeStream::new(EdgeEncoding::U16, 2);
+
+    let error = stream.decode(&bytes).unwrap_err();
+
+    assert_eq!(error.kind, LoadErrorKind::UnsupportedVersion(9));
+    assert_eq!(error.offset, 4);
+}
+
+#[test]
+fn test_unsupported_index_width() {
+    let mut bytes = framed_file(&[&[1], &[]], 2);
+    bytes[6] = 3;
+    let mut stream = EdgeStream::new(EdgeEncoding::U16, 2);
+
+    let error = stream.decode(&bytes).unwrap_err();
+
+    assert_eq!(error.kind, LoadErrorKind::UnsupportedIndexWidth(3));
+    assert_eq!(error.offset, 6);
+}
+
+#[test]
+fn test_node_count_mismatch() {
+    let bytes = framed_file(&[&[1], &[]], 2);
+    let mut stream = EdgeStream::new(EdgeEncoding::U16, 5);
+
+    let error = stream.decode(&bytes).unwrap_err();
+
+    assert_eq!(
+        error.kind,
+        LoadErrorKind::NodeCountMismatch {
+            expected: 5,
+            found: 2
+        }
+    );
+}
+
+#[test]
+fn test_edge_count_mismatch() {
+    let mut bytes = framed_file(&[&[1], &[]], 2);
+    bytes[12] = 4;
+    let mut stream = EdgeStream::new(EdgeEncoding::U16, 2);
+
+    let error = stream.decode(&bytes).unwrap_err();
+
+    assert_eq!(
+        error.kind,
+        LoadErrorKind::EdgeCountMismatch {
+            expected: 4,
+            found: 1
+        }
+    );
+    assert_eq!(error.offset, 22);
+}
+
+#[test]
+fn test_checksum_mismatch() {
+    let mut bytes = framed_file(&[&[1], &[0]], 2);
+    // Swap the targets so the counts still agree but the content differs
+    bytes[16] = 0;
+    bytes[20] = 1;
+    let mut stream = EdgeStream::new(EdgeEncoding::U16, 2);
+
+    let error = stream.decode(&bytes).unwrap_err();
+
+    assert!(matches!(error.kind, LoadErrorKind::ChecksumMismatch { .. }));
+    assert_eq!(error.offset, 24);
+}
+
+#[test]
+fn test_truncated_file() {
+    let bytes = framed_file(&[&[1], &[0]], 2);
+    let mut stream = EdgeStream::new(EdgeEncoding::U16, 2);
+
+    stream.decode(&bytes[..bytes.len() - 2]).unwrap();
+    let error = stream.finish().unwrap_err();
+
+    assert_eq!(error.kind, LoadErrorKind::Truncated);
+    assert_eq!(error.offset, bytes.len() - 2);
+}
+
+#[test]
+fn test_trailing_data() {
+    let mut bytes = framed_file(&[&[1], &[0]], 2);
+    bytes.push(0);
+    let mut stream = EdgeStream::new(EdgeEncoding::U16, 2);
+
+    let error = stream.decode(&bytes).unwrap_err();
+
+    assert_eq!(error.kind, LoadErrorKind::TrailingData);
+    assert_eq!(error.offset, bytes.len() - 1);
+}
+
+#[test]
+fn test_error_message() {
+    let error = LoadError {
+        offset: 6,
+        node_index: 0,
+        kind: LoadErrorKind::UnsupportedIndexWidth(3),
+    };
+
+    assert_eq!(
+        error.to_string(),
+        "Failed to load edges: unsupported index width of 3 bytes at byte 6 (node 0)"
+    );
+}
+
+#[test]
+fn test_target_out_of_range() {
+    let bytes = encode_u16(&[1, u16::MAX, 2, u16::MAX]);
+    let mut stream = EdgeStream::new(EdgeEncoding::U16, 2);
+
+    let error = stream.decode(&bytes).unwrap_err();
+
+    assert_eq!(
+        error,
+        LoadError {
+            offset: 4,
+            node_index: 1,
+            kind: LoadErrorKind::TargetOutOfRange {
+                target: 2,
+                node_count: 2
+            },
+        }
+    );
+}
+
+#[test]
+fn test_too_many_delimiters() {
+    let bytes = encode_u16(&[u16::MAX, u16::MAX, u16::MAX]);
+    let mut stream = EdgeStream::new(EdgeEncoding::U16, 2);
+
+    let error = stream.decode(&bytes).unwrap_err();
+
+    assert_eq!(
+        error,
+        LoadError {
+            offset: 4,
+            node_index: 2,
+            kind: LoadErrorKind::TooManyDelimiters { node_count: 2 },
+        }
+    );
+}
+
+#[test]
+fn test_target_after_last_node() {
+    let bytes = encode_u16(&[u16::MAX, 0]);
+    let mut stream = EdgeStream::new(EdgeEncoding::U16, 1);
+
+    let error = stream.decode(&bytes).unwrap_err();
+
+    assert_eq!(
+        error.kind,
+        LoadErrorKind::EdgeAfterLastNode { node_count: 1 }
+    );
+    assert_eq!(error.kind.reason(), "edge_after_last_node");
+    assert_eq!(error.offset, 2);
+}
+
+#[test]
+fn test_error_is_sticky() {
+    let bytes = encode_u16(&[5, u16::MAX]);
+    let mut stream = EdgeStream::new(EdgeEncoding::U16, 1);
+
+    let first = stream.decode(&bytes).unwrap_err();
+    let second = stream.decode(&encode_u16(&[0])).unwrap_err();
+
+    assert_eq!(first, second);
+    assert_eq!(stream.finish(), Err(first));
+}
+
+#[test]
+fn test_error_reason() {
+    let kind = LoadErrorKind::TargetOutOfRange {
+        target: 9,
+        node_count: 3,
+    };
+
+    assert_eq!(kind.reason(), "target_out_of_range");
+}
+
+#[test]
+fn test_decode_weighted_file_split_mid_weight() {
+    let bytes = weighted_file(&[&[(1, 2.5)], &[(0, 0.5), (1, 4.0)]]);
+    let mut stream = EdgeStream::new(EdgeEncoding::U16, 2);
+
+    let edges = decode_all(&mut stream, &bytes, 3);
+
+    assert_eq!(edges, vec![(0, 1, 2.5), (1, 0, 0.5), (1, 1, 4.0)]);
+    assert!(stream.header().unwrap().weighted);
+    assert_eq!(stream.finish(), Ok(()));
+}
+
+#[test]
+fn test_negative_weight() {
+    let bytes = weighted_file(&[&[(1, -1.0)], &[]]);
+    let mut stream = EdgeStream::new(EdgeEncoding::U16, 2);
+
+    let error = stream.decode(&bytes).unwrap_err();
+
+    assert_eq!(error.kind, LoadErrorKind::InvalidWeight { weight: -1.0 });
+    assert_eq!(error.offset, 18);
+}
+
+#[test]
+fn test_unsupported_flags() {
+    let mut bytes = framed_file(&[&[1], &[]], 2);
+    bytes[7] = 0b1000_0000;
+    let mut stream = EdgeStream::new(EdgeEncoding::U16, 2);
+
+    let error = stream.decode(&bytes).unwrap_err();
+
+    assert_eq!(error.kind, LoadErrorKind::UnsupportedFlags(0b1000_0000));
+    assert_eq!(error.offset, 7);
+}
+
+#[test]
+fn test_decode_varint_file_byte_by_byte() {
+    let node_targets = vec![
+        vec![(1, None), (300, None), (70_000, None)],
+        vec![],
+        vec![(0, None), (0, None), (2, None)],
+    ];
+    let bytes = varint_file(&node_targets, 70_001, 0);
+    let mut stream = EdgeStream::new(EdgeEncoding::U16, 70_001);
+
+    let edges = decode_all(&mut stream, &bytes, 1);
+
+    assert_eq!(
+        edges,
+        vec![
+            (0, 1, 1.0),
+            (0, 300, 1.0),
+            (0, 70_000, 1.0),
+            (2, 0, 1.0),
+            (2, 0, 1.0),
+            (2, 2, 1.0)
+        ]
+    );
+    assert_eq!(stream.header().unwrap().encoding, EdgeEncoding::Varint);
+    assert_eq!(stream.finish(), Ok(()));
+}
+
+#[test]
+fn test_decode_weighted_varint_file_split_mid_weight() {
+    let node_targets = vec![vec![(1, Some(2.5))], vec![(0, Some(0.5)), (1, Some(4.0))]];
+    let bytes = varint_file(&node_targets, 2, FLAG_WEIGHTED);
+    let mut stream = EdgeStream::new(EdgeEncoding::U16, 2);
+
+    let edges = decode_all(&mut stream, &bytes, 3);
+
+    assert_eq!(edges, vec![(0, 1, 2.5), (1, 0, 0.5), (1, 1, 4.0)]);
+    assert_eq!(stream.finish(), Ok(()));
+}
+
+#[test]
+fn test_decode_headerless_varint_stream() {
+    let bytes = encode_varint_body(&[vec![(1, None), (2, None)], vec![], vec![(1, None)]]);
+    let mut stream = EdgeStream::new(EdgeEncoding::Varint, 3);
+
+    let edges = decode_all(&mut stream, &bytes, 2);
+
+    assert_eq!(edges, vec![(0, 1, 1.0), (0, 2, 1.0), (2, 1, 1.0)]);
+    assert_eq!(stream.finish(), Ok(()));
+}
+
+#[test]
+fn test_truncated_varint_stream() {
+    let bytes = encode_varint_body(&[vec![(1, None), (2, None)], vec![]]);
+    let mut stream = EdgeStream::new(EdgeEncoding::Varint, 3);
+
+    stream.decode(&bytes[..2]).unwrap();
+
+    assert_eq!(stream.finish().unwrap_err().kind, LoadErrorKind::Truncated);
+}
+
+#[test]
+fn test_varint_too_long() {
+    let bytes = [1, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F];
+    let mut stream = EdgeStream::new(EdgeEncoding::Varint, 2);
+
+    let error = stream.decode(&bytes).unwrap_err();
+
+    assert_eq!(error.kind, LoadErrorKind::InvalidVarint);
+    assert_eq!(error.offset, 1);
+}
+
+#[test]
+fn test_varint_gap_out_of_range() {
+    let bytes = encode_varint_body(&[vec![(1, None), (3, None)], vec![]]);
+    let mut stream = EdgeStream::new(EdgeEncoding::Varint, 2);
+
+    let error = stream.decode(&bytes).unwrap_err();
+
+    assert_eq!(
+        error.kind,
+        LoadErrorKind::TargetOutOfRange {
+            target: 3,
+            node_count: 2
+        }
+    );
+    assert_eq!(error.offset, 2);
+}
@@ -0,0 +1,104 @@
+//! Node metadata, such as that in nodes.json
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// A node's metadata, with its index in the graph as its id
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Node {
+    #[serde(skip_deserializing)]
+    pub id: usize,
+    pub username: String,
+    #[serde(default)]
+    pub name: String,
+    pub rank: u32,
+    pub x: f32,
+    pub y: f32,
+    /// Any other fields of the node
+    #[serde(flatten)]
+    pub attributes: BTreeMap<String, Value>,
+}
+
+impl Node {
+    /// The node's name, or its username when it has no name
+    pub fn display_name(&self) -> &str {
+        if self.name.is_empty() {
+            &self.username
+        } else {
+            &self.name
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NodeStore {
+    nodes: Vec<Node>,
+}
+
+impl NodeStore {
+    /// Gives each node its index as its id
+    pub fn new(mut nodes: Vec<Node>) -> NodeStore {
+        for (index, node) in nodes.iter_mut().enumerate() {
+            node.id = index;
+        }
+        NodeStore { nodes }
+    }
+
+    /// Parses a JSON array of nodes, in node index order
+    pub fn from_json(json: &str) -> Result<NodeStore, serde_json::Error> {
+        let nodes: Vec<Node> = serde_json::from_str(json)?;
+        Ok(NodeStore::new(nodes))
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Node> {
+        self.nodes.get(id)
+    }
+
+    /// Interleaved x and y coordinates of every node
+    pub fn locations(&self) -> Vec<f32> {
+        self.nodes
+            .iter()
+            .flat_map(|node| [node.x, node.y])
+            .collect()
+    }
+
+    /// Ids of nodes whose username or name contains `query`, ignoring case,
+    /// in rank order
+    pub fn search(&self, query: &str) -> Vec<usize> {
+        let query = query.to_lowercase();
+        let mut matches: Vec<&Node> = self
+            .nodes
+            .iter()
+            .filter(|node| {
+                node.username.to_lowercase().contains(&query)
+                    || node.name.to_lowercase().contains(&query)
+            })
+            .collect();
+        matches.sort_by_key(|node| (node.rank, node.id));
+        matches.iter().map(|node| node.id).collect()
+    }
+
+    /// Sort key that puts nodes in rank order, followed by any nodes beyond
+    /// the store in index order
+    pub fn rank_order(&self, id: usize) -> (bool, u32, usize) {
+        let rank = self.get(id).map(|node| node.rank);
+        (rank.is_none(), rank.unwrap_or(0), id)
+    }
+
+    /// Ids of nodes with an attribute equal to `value`
+    pub fn filter(&self, attribute: &str, value: &Value) -> Vec<usize> {
+        self.nodes
+            .iter()
+            .filter(|node| node.attributes.get(attribute) == Some(value))
+            .map(|node| node.id)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,70 @@
+use super::*;
+
+const NODES_JSON: &str = r#"[
+    {"username": "alice", "name": "Alice Smith", "rank": 2, "x": 1.0, "y": 2.0},
+    {"username": "bob", "name": "", "rank": 1, "x": -1.0, "y": 0.5, "verified": true},
+    {"username": "carol_al", "rank": 3, "x": 0.0, "y": 0.0, "verified": false}
+]"#;
+
+#[test]
+fn test_from_json() {
+    let store = NodeStore::from_json(NODES_JSON).unwrap();
+
+    assert_eq!(store.len(), 3);
+    let bob = store.get(1).unwrap();
+    assert_eq!(bob.id, 1);
+    assert_eq!(bob.username, "bob");
+    assert_eq!(bob.rank, 1);
+    assert_eq!(bob.attributes.get("verified"), Some(&Value::Bool(true)));
+}
+
+#[test]
+fn test_from_json_missing_field() {
+    let result = NodeStore::from_json(r#"[{"username": "alice", "x": 1.0, "y": 2.0}]"#);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_display_name_falls_back_to_username() {
+    let store = NodeStore::from_json(NODES_JSON).unwrap();
+
+    assert_eq!(store.get(0).unwrap().display_name(), "Alice Smith");
+    assert_eq!(store.get(1).unwrap().display_name(), "bob");
+    assert_eq!(store.get(2).unwrap().display_name(), "carol_al");
+}
+
+#[test]
+fn test_locations() {
+    let store = NodeStore::from_json(NODES_JSON).unwrap();
+
+    assert_eq!(store.locations(), vec![1.0, 2.0, -1.0, 0.5, 0.0, 0.0]);
+}
+
+#[test]
+fn test_search_ignores_case_and_orders_by_rank() {
+    let store = NodeStore::from_json(NODES_JSON).unwrap();
+
+    let matches = store.search("AL");
+
+    assert_eq!(matches, vec![0, 2]);
+}
+
+#[test]
+fn test_rank_order_puts_nodes_without_metadata_last() {
+    let store = NodeStore::from_json(NODES_JSON).unwrap();
+    let mut ids: Vec<usize> = (0..5).rev().collect();
+
+    ids.sort_by_key(|&id| store.rank_order(id));
+
+    assert_eq!(ids, vec![1, 0, 2, 3, 4]);
+}
+
+#[test]
+fn test_filter() {
+    let store = NodeStore::from_json(NODES_JSON).unwrap();
+
+    let matches = store.filter("verified", &Value::Bool(false));
+
+    assert_eq!(matches, vec![2]);
+}
@@ -0,0 +1,214 @@
+use crate::geometry::{Points, Rect, Vector2};
+
+const LEAF_CAPACITY: usize = 8;
+// Coincident points can never be separated, so subdivision has to stop somewhere
+const MAX_DEPTH: usize = 24;
+
+/// Region quadtree over a set of points, storing the total mass and centre of
+/// mass of every cell for Barnes-Hut approximation
+///
+/// It also serves as a spatial index, for finding the points in a region.
+pub struct QuadTree {
+    cells: Vec<Cell>,
+    locations: Vec<Vector2>,
+    masses: Vec<f32>,
+}
+
+struct Cell {
+    bounds: Rect,
+    mass: f32,
+    mass_center: Vector2,
+    // Children are stored consecutively: south-west, south-east, north-west, north-east
+    first_child: Option<usize>,
+    members: Vec<usize>,
+}
+
+impl Cell {
+    fn new(bounds: Rect, members: Vec<usize>) -> Cell {
+        Cell {
+            bounds,
+            mass: 0.0,
+            mass_center: Vector2::new(0.0, 0.0),
+            first_child: None,
+            members,
+        }
+    }
+
+    fn size(&self) -> f32 {
+        self.bounds.top_right.x - self.bounds.bottom_left.x
+    }
+
+    fn center(&self) -> Vector2 {
+        (self.bounds.bottom_left + self.bounds.top_right) / 2.0
+    }
+}
+
+impl QuadTree {
+    pub fn new(points: &Points, masses: &[f32]) -> QuadTree {
+        let locations: Vec<Vector2> = points.iter().collect();
+        let root = Cell::new(bounding_square(&locations), (0..locations.len()).collect());
+        let mut tree = QuadTree {
+            cells: vec![root],
+            locations,
+            masses: masses.to_vec(),
+        };
+        tree.subdivide();
+        tree.accumulate_masses();
+        tree
+    }
+
+    fn subdivide(&mut self) {
+        let mut pending = vec![(0, 0)];
+        while let Some((cell_index, depth)) = pending.pop() {
+            let cell = &mut self.cells[cell_index];
+            if cell.members.len() <= LEAF_CAPACITY || depth >= MAX_DEPTH {
+                continue;
+            }
+            let center = cell.center();
+            let bottom_left = cell.bounds.bottom_left;
+            let top_right = cell.bounds.top_right;
+            let mut quadrants: [Vec<usize>; 4] = Default::default();
+            for member in std::mem::take(&mut cell.members) {
+                quadrants[quadrant(center, self.locations[member])].push(member);
+            }
+
+            let first_child = self.cells.len();
+            self.cells[cell_index].first_child = Some(first_child);
+            let child_bounds = [
+                Rect::new(bottom_left, center),
+                Rect::new(
+                    Vector2::new(center.x, bottom_left.y),
+                    Vector2::new(top_right.x, center.y),
+                ),
+                Rect::new(
+                    Vector2::new(bottom_left.x, center.y),
+                    Vector2::new(center.x, top_right.y),
+                ),
+                Rect::new(center, top_right),
+            ];
+            for (offset, (bounds, members)) in child_bounds.into_iter().zip(quadrants).enumerate() {
+                self.cells.push(Cell::new(bounds, members));
+                pending.push((first_child + offset, depth + 1));
+            }
+        }
+    }
+
+    fn accumulate_masses(&mut self) {
+        // Children always come after their parent, so a reverse pass sees
+        // every child before the cell containing it
+        for cell_index in (0..self.cells.len()).rev() {
+            let (mass, weighted_sum) = match self.cells[cell_index].first_child {
+                Some(first_child) => self.cells[first_child..first_child + 4]
+                    .iter()
+                    .fold((0.0, Vector2::new(0.0, 0.0)), |(mass, sum), child| {
+                        (mass + child.mass, sum + child.mass_center * child.mass)
+                    }),
+                None => self.cells[cell_index].members.iter().fold(
+                    (0.0, Vector2::new(0.0, 0.0)),
+                    |(mass, sum), &member| {
+                        let member_mass = self.masses[member];
+                        (
+                            mass + member_mass,
+                            sum + self.locations[member] * member_mass,
+                        )
+                    },
+                ),
+            };
+            let cell = &mut self.cells[cell_index];
+            cell.mass = mass;
+            if mass > 0.0 {
+                cell.mass_center = weighted_sum / mass;
+            }
+        }
+    }
+
+    /// Visits the masses acting on `location`, excluding the point `exclude`
+    ///
+    /// A cell whose size divided by its distance from `location` is below
+    /// `theta` is visited once as a single mass at its centre of mass; other
+    /// cells are opened. A `theta` of zero visits every point exactly.
+    pub fn visit_masses<F>(&self, location: Vector2, theta: f32, exclude: usize, mut visit: F)
+    where
+        F: FnMut(Vector2, f32),
+    {
+        let mut pending = vec![0];
+        while let Some(cell_index) = pending.pop() {
+            let cell = &self.cells[cell_index];
+            if cell.mass == 0.0 {
+                continue;
+            }
+            match cell.first_child {
+                None => {
+                    for &member in &cell.members {
+                        if member != exclude {
+                            visit(self.locations[member], self.masses[member]);
+                        }
+                    }
+                }
+                Some(first_child) => {
+                    let distance = (cell.mass_center - location).magnitude();
+                    let far_enough = cell.size() < theta * distance;
+                    // Points on a split line belong to the cell east or north
+                    // of it, so edges count as inside
+                    if far_enough && !cell.bounds.encloses(location) {
+                        visit(cell.mass_center, cell.mass);
+                    } else {
+                        pending.extend(first_child..first_child + 4);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Visits the index of every point inside or on the edge of `bounds`,
+    /// skipping cells that lie entirely outside it
+    pub fn visit_within<F>(&self, bounds: &Rect, mut visit: F)
+    where
+        F: FnMut(usize),
+    {
+        let mut pending = vec![0];
+        while let Some(cell_index) = pending.pop() {
+            let cell = &self.cells[cell_index];
+            if !cell.bounds.intersects(bounds) {
+                continue;
+            }
+            match cell.first_child {
+                None => {
+                    for &member in &cell.members {
+                        if bounds.encloses(self.locations[member]) {
+                            visit(member);
+                        }
+                    }
+                }
+                Some(first_child) => pending.extend(first_child..first_child + 4),
+            }
+        }
+    }
+}
+
+fn quadrant(center: Vector2, location: Vector2) -> usize {
+    let east = (location.x >= center.x) as usize;
+    let north = (location.y >= center.y) as usize;
+    east + 2 * north
+}
+
+fn bounding_square(locations: &[Vector2]) -> Rect {
+    if locations.is_empty() {
+        return Rect::new(Vector2::new(0.0, 0.0), Vector2::new(0.0, 0.0));
+    }
+    let mut min = locations[0];
+    let mut max = locations[0];
+    for location in locations {
+        min = Vector2::new(min.x.min(location.x), min.y.min(location.y));
+        max = Vector2::new(max.x.max(location.x), max.y.max(location.y));
+    }
+    // Square cells keep the size-to-distance test meaningful in both axes,
+    // and padding keeps points on the maximum edge inside the root
+    let half_size = (max.x - min.x).max(max.y - min.y) / 2.0 + 1.0;
+    let center = (min + max) / 2.0;
+    let half_extent = Vector2::new(half_size, half_size);
+    Rect::new(center - half_extent, center + half_extent)
+}
+
+#[cfg(test)]
+mod tests;
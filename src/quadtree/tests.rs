@@ -0,0 +1,135 @@
+use super::*;
+use crate::random::Rng;
+
+fn random_points(count: usize, seed: u64) -> Points {
+    let mut rng = Rng::new(seed);
+    let data = (0..count * 2)
+        .map(|_| rng.below(10_000) as f32 / 10.0 - 500.0)
+        .collect();
+    Points::new(data)
+}
+
+fn repulsion(tree: &QuadTree, location: Vector2, exclude: usize, theta: f32) -> Vector2 {
+    let mut force = Vector2::new(0.0, 0.0);
+    tree.visit_masses(location, theta, exclude, |center, mass| {
+        let delta = location - center;
+        let distance_squared = delta.x * delta.x + delta.y * delta.y;
+        if distance_squared > 0.0 {
+            force += delta * (mass / distance_squared);
+        }
+    });
+    force
+}
+
+#[test]
+fn test_root_holds_total_mass_and_center() {
+    let points = Points::new(vec![0.0, 0.0, 2.0, 0.0, 0.0, 4.0]);
+    let masses = [1.0, 1.0, 2.0];
+
+    let tree = QuadTree::new(&points, &masses);
+
+    assert_eq!(tree.cells[0].mass, 4.0);
+    assert_eq!(tree.cells[0].mass_center, Vector2::new(0.5, 2.0));
+}
+
+#[test]
+fn test_subdivides_beyond_leaf_capacity() {
+    let points = random_points(100, 1);
+    let masses = vec![1.0; 100];
+
+    let tree = QuadTree::new(&points, &masses);
+
+    assert!(tree.cells.len() > 1);
+    for cell in &tree.cells {
+        assert!(cell.members.len() <= LEAF_CAPACITY);
+    }
+    let leaf_members: usize = tree.cells.iter().map(|cell| cell.members.len()).sum();
+    assert_eq!(leaf_members, 100);
+}
+
+#[test]
+fn test_coincident_points_stop_at_max_depth() {
+    let points = Points::new(vec![1.0; 40]);
+    let masses = vec![1.0; 20];
+
+    let tree = QuadTree::new(&points, &masses);
+
+    assert_eq!(tree.cells[0].mass, 20.0);
+}
+
+#[test]
+fn test_zero_theta_visits_every_other_point() {
+    let points = random_points(50, 2);
+    let masses = vec![1.0; 50];
+    let tree = QuadTree::new(&points, &masses);
+
+    let mut visited = 0;
+    tree.visit_masses(points.get_point(3), 0.0, 3, |_, _| visited += 1);
+
+    assert_eq!(visited, 49);
+}
+
+#[test]
+fn test_point_on_split_line_excludes_itself() {
+    // The root splits at the origin, which puts point 0 on the south-west
+    // corner of the north-east cell along with the ten points above it
+    let mut data = vec![0.0, 0.0, -10.0, -10.0];
+    data.extend((1..=10).flat_map(|step| [step as f32, step as f32]));
+    let points = Points::new(data);
+    let masses = vec![1.0; 12];
+    let tree = QuadTree::new(&points, &masses);
+
+    let mut visited_mass = 0.0;
+    tree.visit_masses(points.get_point(0), 10.0, 0, |_, mass| visited_mass += mass);
+
+    assert_eq!(visited_mass, 11.0);
+}
+
+#[test]
+fn test_approximation_close_to_exact() {
+    let points = random_points(500, 3);
+    let masses = vec![1.0; 500];
+    let tree = QuadTree::new(&points, &masses);
+
+    for index in [0, 17, 250, 499] {
+        let location = points.get_point(index);
+        let exact = repulsion(&tree, location, index, 0.0);
+        let approximate = repulsion(&tree, location, index, 0.5);
+        let error = (exact - approximate).magnitude() / exact.magnitude();
+        assert!(error < 0.05, "relative error {} too large", error);
+    }
+}
+
+#[test]
+fn test_visit_within_matches_linear_scan() {
+    let points = random_points(500, 4);
+    let masses = vec![1.0; 500];
+    let tree = QuadTree::new(&points, &masses);
+    let bounds = Rect::new(Vector2::new(-120.0, -80.0), Vector2::new(60.0, 200.0));
+
+    let mut visited = Vec::new();
+    tree.visit_within(&bounds, |index| visited.push(index));
+    visited.sort();
+
+    let expected: Vec<usize> = points
+        .iter()
+        .enumerate()
+        .filter(|&(_, location)| bounds.encloses(location))
+        .map(|(index, _)| index)
+        .collect();
+    assert!(!expected.is_empty());
+    assert_eq!(visited, expected);
+}
+
+#[test]
+fn test_visit_within_outside_points() {
+    let points = random_points(100, 5);
+    let masses = vec![1.0; 100];
+    let tree = QuadTree::new(&points, &masses);
+    let bounds = Rect::new(Vector2::new(600.0, 600.0), Vector2::new(700.0, 700.0));
+
+    let mut visited = 0;
+    tree.visit_within(&bounds, |_| visited += 1);
+
+    assert_eq!(visited, 0);
+}
@@ -0,0 +1,181 @@
+use crate::GraphLayout;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DegreeKind {
+    In,
+    Out,
+    Total,
+}
+
+pub fn degrees(layout: &GraphLayout, kind: DegreeKind) -> Vec<u32> {
+    (0..layout.node_count())
+        .map(|node| {
+            let degree = match kind {
+                DegreeKind::In => layout.sources(node).len(),
+                DegreeKind::Out => layout.targets(node).len(),
+                DegreeKind::Total => layout.sources(node).len() + layout.targets(node).len(),
+            };
+            degree as u32
+        })
+        .collect()
+}
+
+/// Estimates each node's final degree while edges are still streaming in
+///
+/// Edges arrive grouped by source, so the first `complete_sources` nodes
+/// already have their final out-degree, and later nodes are assumed to have
+/// at least the mean of those. In-degrees are scaled up by the fraction of
+/// edges still to come. Once `progress` reaches one the degrees are exact.
+pub fn estimated_degrees(
+    layout: &GraphLayout,
+    kind: DegreeKind,
+    complete_sources: usize,
+    progress: f32,
+) -> Vec<f32> {
+    if progress >= 1.0 {
+        return degrees(layout, kind)
+            .into_iter()
+            .map(|degree| degree as f32)
+            .collect();
+    }
+    let complete_sources = complete_sources.min(layout.node_count());
+    let complete_out_degree: usize = (0..complete_sources)
+        .map(|node| layout.targets(node).len())
+        .sum();
+    let mean_out_degree = if complete_sources > 0 {
+        complete_out_degree as f32 / complete_sources as f32
+    } else {
+        0.0
+    };
+    let in_scale = if progress > 0.0 { 1.0 / progress } else { 1.0 };
+    (0..layout.node_count())
+        .map(|node| {
+            let out_degree = layout.targets(node).len() as f32;
+            let out_estimate = if node < complete_sources {
+                out_degree
+            } else {
+                out_degree.max(mean_out_degree)
+            };
+            let in_estimate = layout.sources(node).len() as f32 * in_scale;
+            match kind {
+                DegreeKind::In => in_estimate,
+                DegreeKind::Out => out_estimate,
+                DegreeKind::Total => in_estimate + out_estimate,
+            }
+        })
+        .collect()
+}
+
+/// Sums the weights of each node's edges, which equals the degree for
+/// unweighted graphs
+pub fn strengths(layout: &GraphLayout, kind: DegreeKind) -> Vec<f32> {
+    (0..layout.node_count())
+        .map(|node| {
+            let in_strength: f32 = layout.source_weights(node).iter().sum();
+            let out_strength: f32 = layout.target_weights(node).iter().sum();
+            match kind {
+                DegreeKind::In => in_strength,
+                DegreeKind::Out => out_strength,
+                DegreeKind::Total => in_strength + out_strength,
+            }
+        })
+        .collect()
+}
+
+/// Counts how many nodes have each degree, indexed by degree
+pub fn histogram(degrees: &[u32]) -> Vec<u32> {
+    let max_degree = degrees.iter().copied().max().unwrap_or(0) as usize;
+    let mut counts = vec![0; max_degree + 1];
+    for &degree in degrees {
+        counts[degree as usize] += 1;
+    }
+    counts
+}
+
+/// Fraction of nodes with degree of at least k, indexed by k
+pub fn ccdf(histogram: &[u32]) -> Vec<f32> {
+    let node_count: u32 = histogram.iter().sum();
+    if node_count == 0 {
+        return Vec::new();
+    }
+    let mut remaining = node_count;
+    histogram
+        .iter()
+        .map(|&count| {
+            let fraction = remaining as f32 / node_count as f32;
+            remaining -= count;
+            fraction
+        })
+        .collect()
+}
+
+/// Estimates the exponent of a power-law tail starting at `min_degree`
+///
+/// Uses the discrete maximum likelihood approximation from Clauset, Shalizi
+/// and Newman (2009). Returns `None` when no node has at least `min_degree`.
+pub fn power_law_exponent(degrees: &[u32], min_degree: u32) -> Option<f32> {
+    let min_degree = min_degree.max(1);
+    let shifted_min = min_degree as f64 - 0.5;
+    let (tail_count, log_sum) = degrees
+        .iter()
+        .filter(|&&degree| degree >= min_degree)
+        .fold((0, 0.0), |(count, sum), &degree| {
+            (count + 1, sum + (degree as f64 / shifted_min).ln())
+        });
+    if tail_count == 0 || log_sum == 0.0 {
+        return None;
+    }
+    Some((1.0 + tail_count as f64 / log_sum) as f32)
+}
+
+#[wasm_bindgen]
+pub struct DegreeDistribution {
+    degrees: Vec<u32>,
+    histogram: Vec<u32>,
+}
+
+#[wasm_bindgen]
+impl DegreeDistribution {
+    pub fn degrees(&self) -> js_sys::Uint32Array {
+        js_sys::Uint32Array::from(self.degrees.as_slice())
+    }
+
+    /// Number of nodes with each degree, indexed by degree
+    pub fn histogram(&self) -> js_sys::Uint32Array {
+        js_sys::Uint32Array::from(self.histogram.as_slice())
+    }
+
+    /// Fraction of nodes with degree of at least k, indexed by k
+    pub fn ccdf(&self) -> js_sys::Float32Array {
+        js_sys::Float32Array::from(ccdf(&self.histogram).as_slice())
+    }
+
+    pub fn max_degree(&self) -> u32 {
+        self.histogram.len().saturating_sub(1) as u32
+    }
+
+    pub fn mean_degree(&self) -> f32 {
+        if self.degrees.is_empty() {
+            return 0.0;
+        }
+        let total: u64 = self.degrees.iter().map(|&degree| degree as u64).sum();
+        total as f32 / self.degrees.len() as f32
+    }
+
+    pub fn power_law_exponent(&self, min_degree: u32) -> Option<f32> {
+        power_law_exponent(&self.degrees, min_degree)
+    }
+}
+
+impl DegreeDistribution {
+    pub fn new(layout: &GraphLayout, kind: DegreeKind) -> DegreeDistribution {
+        let degrees = degrees(layout, kind);
+        let histogram = histogram(&degrees);
+        DegreeDistribution { degrees, histogram }
+    }
+}
+
+#[cfg(test)]
+mod tests;
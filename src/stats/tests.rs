@@ -0,0 +1,93 @@
+use super::*;
+
+#[test]
+fn test_degrees_by_kind() {
+    let layout = GraphLayout::from_edges(3, &[(0, 1), (0, 2), (1, 2)]);
+
+    assert_eq!(degrees(&layout, DegreeKind::In), vec![0, 1, 2]);
+    assert_eq!(degrees(&layout, DegreeKind::Out), vec![2, 1, 0]);
+    assert_eq!(degrees(&layout, DegreeKind::Total), vec![2, 2, 2]);
+}
+
+#[test]
+fn test_degrees_after_out_of_order_chunks() {
+    let mut layout = GraphLayout::from_edges(3, &[(1, 2)]);
+
+    layout.add_edges(&[(0, 2, 1.0), (2, 0, 1.0)]).unwrap();
+    let in_degrees = degrees(&layout, DegreeKind::In);
+    layout.add_edges(&[(1, 0, 1.0), (0, 1, 1.0)]).unwrap();
+
+    assert_eq!(in_degrees, vec![1, 0, 2]);
+    assert_eq!(degrees(&layout, DegreeKind::In), vec![2, 1, 2]);
+    assert_eq!(degrees(&layout, DegreeKind::Out), vec![2, 2, 1]);
+}
+
+#[test]
+fn test_estimated_degrees_mid_load() {
+    // Half the edges have arrived, with nodes 0 and 1 complete
+    let layout = GraphLayout::from_edges(4, &[(0, 1), (0, 2), (1, 2), (1, 3)]);
+
+    let out_estimate = estimated_degrees(&layout, DegreeKind::Out, 2, 0.5);
+    let in_estimate = estimated_degrees(&layout, DegreeKind::In, 2, 0.5);
+
+    assert_eq!(out_estimate, vec![2.0, 2.0, 2.0, 2.0]);
+    assert_eq!(in_estimate, vec![0.0, 2.0, 4.0, 2.0]);
+}
+
+#[test]
+fn test_estimated_degrees_once_loaded() {
+    let layout = GraphLayout::from_edges(3, &[(0, 1), (0, 2), (1, 2)]);
+
+    let estimate = estimated_degrees(&layout, DegreeKind::Total, 0, 1.0);
+
+    assert_eq!(estimate, vec![2.0, 2.0, 2.0]);
+}
+
+#[test]
+fn test_histogram_counts_each_degree() {
+    let degrees = [0, 2, 2, 3, 0, 2];
+
+    assert_eq!(histogram(&degrees), vec![2, 0, 3, 1]);
+}
+
+#[test]
+fn test_histogram_empty() {
+    assert_eq!(histogram(&[]), vec![0]);
+}
+
+#[test]
+fn test_ccdf_starts_at_one_and_decreases() {
+    let counts = [2, 0, 3, 1];
+
+    assert_eq!(ccdf(&counts), vec![1.0, 4.0 / 6.0, 4.0 / 6.0, 1.0 / 6.0]);
+}
+
+#[test]
+fn test_ccdf_empty() {
+    assert!(ccdf(&[0]).is_empty());
+}
+
+#[test]
+fn test_power_law_exponent_matches_formula() {
+    let degrees = [1, 1, 2, 4, 8];
+
+    let exponent = power_law_exponent(&degrees, 2).unwrap();
+
+    let log_sum = (2.0_f64 / 1.5).ln() + (4.0_f64 / 1.5).ln() + (8.0_f64 / 1.5).ln();
+    let expected = (1.0 + 3.0 / log_sum) as f32;
+    assert!((exponent - expected).abs() < 1e-5);
+}
+
+#[test]
+fn test_power_law_exponent_without_tail() {
+    assert_eq!(power_law_exponent(&[1, 2, 3], 10), None);
+}
+
+#[test]
+fn test_strengths_sum_edge_weights() {
+    let layout = GraphLayout::from_weighted_edges(3, &[(0, 1, 2.0), (0, 2, 0.5), (1, 2, 1.5)]);
+
+    assert_eq!(strengths(&layout, DegreeKind::In), vec![0.0, 2.0, 2.0]);
+    assert_eq!(strengths(&layout, DegreeKind::Out), vec![2.5, 1.5, 0.0]);
+    assert_eq!(strengths(&layout, DegreeKind::Total), vec![2.5, 3.5, 2.0]);
+}
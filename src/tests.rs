@@ -0,0 +1,163 @@
+use super::*;
+
+#[test]
+fn test_out_of_order_edges_keep_their_order_within_rows() {
+    let mut layout = GraphLayout::from_edges(3, &[(1, 2)]);
+
+    layout.add_edges(&[(0, 2, 1.0), (2, 0, 1.0)]).unwrap();
+    let first_targets = layout.targets(0).to_vec();
+    layout.add_edges(&[(1, 0, 1.0), (0, 1, 1.0)]).unwrap();
+
+    assert_eq!(first_targets, vec![2]);
+    assert_eq!(layout.edge_count(), 5);
+    assert_eq!(layout.targets(0), &[2, 1]);
+    assert_eq!(layout.targets(1), &[2, 0]);
+    assert_eq!(layout.sources(2), &[0, 1]);
+}
+
+#[test]
+fn test_edges_out_of_range_are_rejected() {
+    let mut layout = GraphLayout::from_edges(3, &[(0, 1)]);
+
+    let result = layout.add_edges(&[(1, 2, 1.0), (2, 3, 1.0)]);
+
+    assert_eq!(
+        result,
+        Err(adjacency::NodeOutOfRange {
+            node: 3,
+            node_count: 3
+        })
+    );
+    assert_eq!(layout.edge_count(), 1);
+    assert_eq!(layout.targets(1), &[] as &[u32]);
+}
+
+#[test]
+fn test_compact_edges_merges_unsorted_edges() {
+    let mut layout = GraphLayout::from_edges(3, &[(2, 0)]);
+    layout.add_edges(&[(0, 1, 1.0), (2, 1, 2.0)]).unwrap();
+
+    layout.compact_edges();
+
+    assert!(layout.unsorted_edges.is_empty());
+    assert!(layout.merged_targets.get().is_none());
+    assert_eq!(layout.edge_count(), 3);
+    assert_eq!(layout.targets(2), &[0, 1]);
+    assert_eq!(layout.target_weights(2), &[1.0, 2.0]);
+    assert_eq!(layout.sources(1), &[0, 2]);
+}
+
+#[test]
+fn test_empty_graph_can_be_displayed() {
+    let layout = GraphLayout::from_locations(0, Vec::new());
+
+    let display = GraphDisplay::new(layout, 800.0, 600.0, 1.0, 1.0, 0).unwrap();
+
+    assert_eq!(display.display_offset, geometry::Vector2::new(0.0, 0.0));
+}
+
+#[test]
+fn test_focus_node_out_of_range() {
+    let layout = GraphLayout::from_locations(3, vec![0.0; 6]);
+
+    let result = GraphDisplay::new(layout, 800.0, 600.0, 1.0, 1.0, 3);
+
+    assert_eq!(
+        result.err(),
+        Some(FocusOutOfRange {
+            focus_node: 3,
+            node_count: 3
+        })
+    );
+}
+
+/// Three nodes 10 page pixels apart, with node 0 at the centre of a 200 by
+/// 100 pixel display
+fn display_of_three_nodes() -> GraphDisplay {
+    let layout = GraphLayout::from_locations(3, vec![0.0, 0.0, 2.0, 0.0, 0.0, 2.0]);
+    GraphDisplay::new(layout, 200.0, 100.0, 0.1, 1.0, 0).unwrap()
+}
+
+#[test]
+fn test_release_restores_pin_state_from_before_drag() {
+    let mut display = display_of_three_nodes();
+    display.layout.set_pinned(1, true);
+
+    display.drag_node(1, 120.0, 50.0);
+    display.release_node(1);
+    display.drag_node(2, 100.0, 30.0);
+    let pinned_during_drag = display.layout.is_pinned(2);
+    display.release_node(2);
+
+    assert!(display.layout.is_pinned(1));
+    assert!(pinned_during_drag);
+    assert!(!display.layout.is_pinned(2));
+}
+
+#[test]
+fn test_drag_ignores_unknown_nodes() {
+    let mut display = display_of_three_nodes();
+    let revision = display.layout.locations_revision();
+
+    display.drag_node(3, 120.0, 50.0);
+    display.release_node(3);
+
+    assert!(display.dragged.is_none());
+    assert_eq!(display.layout.locations_revision(), revision);
+}
+
+#[test]
+fn test_dragging_another_node_releases_the_first() {
+    let mut display = display_of_three_nodes();
+
+    display.drag_node(1, 120.0, 50.0);
+    display.drag_node(2, 100.0, 30.0);
+
+    assert!(!display.layout.is_pinned(1));
+    assert!(display.layout.is_pinned(2));
+    assert_eq!(display.dragged, Some((2, false)));
+}
+
+#[test]
+fn test_node_at_picks_nearest_node_within_radius() {
+    let display = display_of_three_nodes();
+
+    assert_eq!(display.node_at(108.0, 50.0, 5.0), Some(1));
+    assert_eq!(display.node_at(103.0, 50.0, 5.0), Some(0));
+    assert_eq!(display.node_at(100.0, 42.0, 5.0), Some(2));
+    assert_eq!(display.node_at(104.0, 50.0, 3.0), None);
+    assert_eq!(display.node_at(150.0, 80.0, 5.0), None);
+}
+
+#[test]
+fn test_edge_alpha_bands_are_spaced_in_log_weight() {
+    let bands: Vec<usize> = [0.0, 3.0, 10.0, 100.0, 1000.0]
+        .iter()
+        .map(|&weight| edge_alpha_band(weight, 1000.0))
+        .collect();
+
+    assert_eq!(bands, vec![0, 0, 1, 2, 3]);
+}
+
+#[test]
+fn test_unweighted_edges_are_opaque() {
+    let layout = GraphLayout::from_edges(3, &[(0, 1), (1, 2), (2, 0)]);
+    let mut display = GraphDisplay::new(layout, 200.0, 100.0, 1.0, 1.0, 0).unwrap();
+
+    display.update_edges();
+
+    assert_eq!(display.get_edge_band_offsets(), &[0, 0, 0, 0, 6]);
+    assert_eq!(edge_band_alpha(EDGE_ALPHA_BANDS - 1), 1.0);
+}
+
+#[test]
+fn test_update_edges_groups_edges_by_band() {
+    let edges = [(0, 1, 100.0), (1, 2, 1000.0), (2, 0, 3.0), (0, 2, 10.0)];
+    let layout = GraphLayout::from_weighted_edges(3, &edges);
+    let mut display = GraphDisplay::new(layout, 200.0, 100.0, 1.0, 1.0, 0).unwrap();
+
+    display.update_edges();
+
+    assert_eq!(display.get_edge_band_offsets(), &[0, 2, 4, 6, 8]);
+    assert_eq!(display.vertex_indices, vec![2, 0, 0, 2, 0, 1, 1, 2]);
+}